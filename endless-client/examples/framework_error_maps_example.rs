@@ -0,0 +1,24 @@
+//! Regenerate the bundled framework error maps from the Endless framework move sources,
+//! e.g. the `move-stdlib`, `endless-stdlib` and `endless-framework` packages:
+//!
+//! ```text
+//! cargo run -p endless-client --example framework_error_maps_example -- \
+//!     <framework>/move-stdlib/sources <framework>/endless-stdlib/sources \
+//!     <framework>/endless-framework/sources \
+//!     > endless-client/src/sdk_ext/error_maps/framework.json
+//! ```
+use endless_client::sdk_ext::abort::ErrorMaps;
+
+fn main() -> anyhow::Result<()> {
+    let dirs: Vec<String> = std::env::args().skip(1).collect();
+    if dirs.is_empty() {
+        anyhow::bail!("usage: framework_error_maps_example <move sources dir>...");
+    }
+
+    let mut maps = ErrorMaps::default();
+    for dir in dirs {
+        maps = maps.with_move_sources(dir)?;
+    }
+    println!("{}", maps.to_json()?);
+    Ok(())
+}
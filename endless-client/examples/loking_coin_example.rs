@@ -1,62 +1,43 @@
 use base_infra::result::AppResult;
-use endless_client::client::EnhancedClient;
-use endless_client::sdk_ext::types::ViewFnArgs;
-use endless_client::utils::bcs_ext::BcsExt;
-use endless_sdk::move_types::account_address::AccountAddress;
 use chain_types::endless::eds_addr_ext::ToEdsAddr;
+use endless_client::client::EnhancedClient;
+use endless_client::client::locking_coin_client::LockingCoinClientTrait;
+use endless_client::client::types::{Owner, Token};
 
-base_infra::gen_impl_code_enum! {
-     TestErr {
-        GetLockingCoinErr = ("TST001", "Get locking_coin_ex failed"),
-
-     }
+fn eds_and_staker() -> AppResult<(Token, Owner)> {
+    let eds = "ENDLESSsssssssssssssssssssssssssssssssssssss".to_eds_addr()?;
+    let staker = "FUH7QsNDZmsFLEXrycR9pXQer2BSgGoUQ84b4RVJkWND".to_eds_addr()?;
+    Ok((Token::new(&eds), Owner::new(&staker)))
 }
 
 // unlocked_balance
 async fn test_unlocked_balance(client: &EnhancedClient) -> AppResult<()> {
-    let eds = "ENDLESSsssssssssssssssssssssssssssssssssssss".to_eds_addr()?;
-    let recipient = "FUH7QsNDZmsFLEXrycR9pXQer2BSgGoUQ84b4RVJkWND".to_eds_addr()?;
-    let args = vec![eds.to_bytes()?, recipient.to_bytes()?];
-
-    let mod_addr = "0x1".to_eds_addr()?;
-    let (mod_name, fn_name) = ("locking_coin_ex", "unlocked_balance");
-    let args = ViewFnArgs::new(mod_addr, mod_name, fn_name, args, vec![])?;
-    let res: u128 = client
-        .view_fn(args, &TestErr::GetLockingCoinErr, None)
-        .await?;
+    let (eds, staker) = eds_and_staker()?;
+    let res = client.unlocked_balance(eds, staker).await?;
     println!("unlocked_balance: {}", res);
     Ok(())
 }
 
-/// Unlock amount and when to unlock.
-#[derive(Debug, Clone, serde::Deserialize)]
-struct UnlockAt {
-    epoch: u64,
-    amount: u128,
-}
-
-/// Unlocked token amount when and how much to unlock.
-#[derive(Debug, Clone, serde::Deserialize)]
-struct UnlockInfo {
-    address: AccountAddress,
-    unlocked: u128,
-    unlock_list: Vec<UnlockAt>,
-}
 async fn test_get_unlock_info(client: &EnhancedClient) -> AppResult<()> {
-    let eds = "ENDLESSsssssssssssssssssssssssssssssssssssss".to_eds_addr()?;
-    let recipient = "FUH7QsNDZmsFLEXrycR9pXQer2BSgGoUQ84b4RVJkWND".to_eds_addr()?;
-    let args = vec![eds.to_bytes()?, recipient.to_bytes()?];
-
-    let mod_addr = "0x1".to_eds_addr()?;
-    let (mod_name, fn_name) = ("locking_coin_ex", "get_unlock_info");
-    let args = ViewFnArgs::new(mod_addr, mod_name, fn_name, args, vec![])?;
-    let res: UnlockInfo = client
-        .view_fn(args, &TestErr::GetLockingCoinErr, None)
-        .await?;
+    let (eds, staker) = eds_and_staker()?;
+    let res = client.unlock_info(eds, staker).await?;
     println!("get_unlock_info: {:?}", res);
     Ok(())
 }
 
+async fn test_unlock_schedule(client: &EnhancedClient) -> AppResult<()> {
+    let (eds, staker) = eds_and_staker()?;
+    let schedule = client.unlock_schedule(eds, staker).await?;
+    println!(
+        "epoch {}: unlocked {}, locked {}, next unlock {:?}",
+        schedule.epoch,
+        schedule.unlocked,
+        schedule.locked(),
+        schedule.next_unlock()
+    );
+    Ok(())
+}
+
 // ido addr
 // 9MprBvkH5jJnK1LaHnuBMAPBQwytF1qBUBvaC8R5vYT8
 //
@@ -79,35 +60,20 @@ async fn test_get_unlock_info(client: &EnhancedClient) -> AppResult<()> {
 // HWcPpjxKPsTwDbsGbzMj82jV4sb5RwHocGXsQ9tcF9sA
 
 async fn test_get_stakers_unlock_info(client: &EnhancedClient) -> AppResult<()> {
-    let eds = "ENDLESSsssssssssssssssssssssssssssssssssssss".to_eds_addr()?;
+    let (eds, _) = eds_and_staker()?;
     let stakers = vec![
         "FUH7QsNDZmsFLEXrycR9pXQer2BSgGoUQ84b4RVJkWND".to_eds_addr()?,
         "HWcPpjxKPsTwDbsGbzMj82jV4sb5RwHocGXsQ9tcF9sA".to_eds_addr()?,
     ];
-    let args = vec![eds.to_bytes()?, stakers.to_bytes()?];
-
-    let mod_addr = "0x1".to_eds_addr()?;
-    let (mod_name, fn_name) = ("locking_coin_ex", "get_stakers_unlock_info");
-    let args = ViewFnArgs::new(mod_addr, mod_name, fn_name, args, vec![])?;
-    let res: Vec<UnlockInfo> = client
-        .view_fn(args, &TestErr::GetLockingCoinErr, None)
-        .await?;
+    let res = client.stakers_unlock_info(eds, stakers).await?;
     println!("get_stakers_unlock_info: {:?}", res);
     Ok(())
 }
 
 // staking_amount
 async fn test_staking_amount(client: &EnhancedClient) -> AppResult<()> {
-    let eds = "ENDLESSsssssssssssssssssssssssssssssssssssss".to_eds_addr()?;
-    let recipient = "FUH7QsNDZmsFLEXrycR9pXQer2BSgGoUQ84b4RVJkWND".to_eds_addr()?;
-    let args = vec![eds.to_bytes()?, recipient.to_bytes()?];
-
-    let mod_addr = "0x1".to_eds_addr()?;
-    let (mod_name, fn_name) = ("locking_coin_ex", "staking_amount");
-    let args = ViewFnArgs::new(mod_addr, mod_name, fn_name, args, vec![])?;
-    let res: u128 = client
-        .view_fn(args, &TestErr::GetLockingCoinErr, None)
-        .await?;
+    let (eds, staker) = eds_and_staker()?;
+    let res = client.staking_amount(eds, staker).await?;
     println!("staking_amount: {}", res);
     Ok(())
 }
@@ -128,6 +94,10 @@ async fn main() -> anyhow::Result<()> {
     test_get_unlock_info(&client).await?;
     println!("---------------------------------------");
 
+    // unlock schedule at the current epoch
+    test_unlock_schedule(&client).await?;
+    println!("---------------------------------------");
+
     // get_stakers_unlock_info
     test_get_stakers_unlock_info(&client).await?;
     println!("---------------------------------------");
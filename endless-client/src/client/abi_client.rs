@@ -0,0 +1,93 @@
+use crate::client::EnhancedClient;
+use crate::error::EdsErr;
+use crate::sdk_ext::abi::{AbiArg, AbiFnKind, ModuleAbiCache, encode_args, find_fn};
+use crate::sdk_ext::types::{EntryFnArgs, ViewFnArgs, parse_type_tags};
+use base_infra::result::AppResult;
+use base_infra::{app_err, map_err};
+use endless_sdk::move_types::account_address::AccountAddress;
+use endless_sdk::rest_client::endless_api_types::MoveModule;
+use endless_sdk::types::LocalAccount;
+
+#[async_trait::async_trait]
+pub trait AbiClientTrait {
+    /// Module abi from the node, cached per node url
+    async fn module_abi(&self, address: AccountAddress, module: &str) -> AppResult<MoveModule>;
+
+    /// Entry function args with `args` encoded against the function abi
+    async fn abi_entry_fn_args<'a>(
+        &self,
+        signer: &'a LocalAccount,
+        address: AccountAddress,
+        module: &'a str,
+        fun: &'a str,
+        args: Vec<AbiArg>,
+        type_args: Vec<&'a str>,
+    ) -> AppResult<EntryFnArgs<'a>>;
+
+    /// View function args with `args` encoded against the function abi
+    async fn abi_view_fn_args(
+        &self,
+        address: AccountAddress,
+        module: &str,
+        fun: &str,
+        args: Vec<AbiArg>,
+        type_args: Vec<&str>,
+    ) -> AppResult<ViewFnArgs>;
+}
+
+#[async_trait::async_trait]
+impl AbiClientTrait for EnhancedClient {
+    async fn module_abi(&self, address: AccountAddress, module: &str) -> AppResult<MoveModule> {
+        let node_url = self.client.path_prefix_string();
+        if let Some(abi) = ModuleAbiCache.get(&node_url, &address, module).await {
+            return Ok(abi);
+        }
+
+        let module_id = format!("{address}::{module}");
+        let resp = self
+            .retry
+            .run("get_account_module", || {
+                self.client.get_account_module(address, module)
+            })
+            .await
+            .map_err(map_err!(&EdsErr::GetModuleAbi, module_id.clone()))?;
+
+        let bytecode = resp.into_inner().try_parse_abi();
+        let bytecode = bytecode.map_err(map_err!(&EdsErr::GetModuleAbi, module_id.clone()))?;
+        let abi = bytecode
+            .abi
+            .ok_or_else(|| app_err!(&EdsErr::GetModuleAbi, format!("{module_id} has no abi")))?;
+
+        ModuleAbiCache.set(&node_url, &address, abi.clone()).await;
+        Ok(abi)
+    }
+
+    async fn abi_entry_fn_args<'a>(
+        &self,
+        signer: &'a LocalAccount,
+        address: AccountAddress,
+        module: &'a str,
+        fun: &'a str,
+        args: Vec<AbiArg>,
+        type_args: Vec<&'a str>,
+    ) -> AppResult<EntryFnArgs<'a>> {
+        let abi = self.module_abi(address, module).await?;
+        let func = find_fn(&abi, fun, AbiFnKind::Entry)?;
+        let args = encode_args(func, &parse_type_tags(type_args.clone())?, args)?;
+        EntryFnArgs::new(signer, address, module, fun, args, type_args)
+    }
+
+    async fn abi_view_fn_args(
+        &self,
+        address: AccountAddress,
+        module: &str,
+        fun: &str,
+        args: Vec<AbiArg>,
+        type_args: Vec<&str>,
+    ) -> AppResult<ViewFnArgs> {
+        let abi = self.module_abi(address, module).await?;
+        let func = find_fn(&abi, fun, AbiFnKind::View)?;
+        let args = encode_args(func, &parse_type_tags(type_args.clone())?, args)?;
+        ViewFnArgs::new(address, module, fun, args, type_args)
+    }
+}
@@ -145,10 +145,8 @@ impl AcctClientTrait for EnhancedClient {
         receiver: AccountAddress,
         overrides: Option<Overrides>,
     ) -> AppResult<Response<PendingTransaction>> {
-        let (mn, fun, args) = ("faucet", "fund", vec![receiver.to_bytes()?]);
-        let fn_args = EntryFnArgs::new(signer, AccountAddress::ONE, mn, fun, args, vec![])?
-            .with_overrides(overrides);
-        self.rest_client().entry_fun(fn_args).await
+        let fn_args = faucet_args(signer, receiver, overrides)?;
+        self.submit_entry_fn(fn_args).await
     }
 
     async fn faucet_wait_txn(
@@ -168,12 +166,8 @@ impl AcctClientTrait for EnhancedClient {
         amount: u128,
         overrides: Option<Overrides>,
     ) -> AppResult<Response<Vec<UserTransaction>>> {
-        let args = vec![to.to_bytes()?, amount.to_bytes()?];
-        let (mn, fun, owner) = ("endless_account", "transfer", from);
-
-        let fn_args = EntryFnArgs::new(owner, AccountAddress::ONE, mn, fun, args, vec![])?
-            .with_overrides(overrides);
-        self.rest_client().simulate_fun(fn_args).await
+        let fn_args = transfer_args(from, to, amount, overrides)?;
+        self.simulate_entry_fn(fn_args).await
     }
 
     async fn transfer(
@@ -183,12 +177,8 @@ impl AcctClientTrait for EnhancedClient {
         amount: u128,
         overrides: Option<Overrides>,
     ) -> AppResult<Response<PendingTransaction>> {
-        let args = vec![to.to_bytes()?, amount.to_bytes()?];
-        let (mn, fun, owner) = ("endless_account", "transfer", from);
-
-        let fn_args = EntryFnArgs::new(owner, AccountAddress::ONE, mn, fun, args, vec![])?
-            .with_overrides(overrides);
-        self.rest_client().entry_fun(fn_args).await
+        let fn_args = transfer_args(from, to, amount, overrides)?;
+        self.submit_entry_fn(fn_args).await
     }
 
     async fn transfer_wait_txn(
@@ -210,13 +200,8 @@ impl AcctClientTrait for EnhancedClient {
         amount: u128,
         overrides: Option<Overrides>,
     ) -> AppResult<Response<Vec<UserTransaction>>> {
-        let args = vec![to.to_bytes()?, amount.to_bytes()?, token.to_bytes()?];
-        let (mn, fun, owner) = ("endless_account", "transfer_coins", from);
-        let t_args = vec!["0x1::fungible_asset::Metadata"];
-
-        let fn_args = EntryFnArgs::new(owner, AccountAddress::ONE, mn, fun, args, t_args)?
-            .with_overrides(overrides);
-        self.rest_client().simulate_fun(fn_args).await
+        let fn_args = transfer_token_args(from, to, token, amount, overrides)?;
+        self.simulate_entry_fn(fn_args).await
     }
 
     async fn transfer_token(
@@ -227,13 +212,8 @@ impl AcctClientTrait for EnhancedClient {
         amount: u128,
         overrides: Option<Overrides>,
     ) -> AppResult<Response<PendingTransaction>> {
-        let args = vec![to.to_bytes()?, amount.to_bytes()?, token.to_bytes()?];
-        let (mn, fun, owner) = ("endless_account", "transfer_coins", from);
-        let t_args = vec!["0x1::fungible_asset::Metadata"];
-
-        let fn_args = EntryFnArgs::new(owner, AccountAddress::ONE, mn, fun, args, t_args)?
-            .with_overrides(overrides);
-        self.rest_client().entry_fun(fn_args).await
+        let fn_args = transfer_token_args(from, to, token, amount, overrides)?;
+        self.submit_entry_fn(fn_args).await
     }
 
     async fn transfer_token_wait_txn(
@@ -250,3 +230,41 @@ impl AcctClientTrait for EnhancedClient {
         self.wait_for_txn(res.inner()).await
     }
 }
+
+pub(crate) fn faucet_args(
+    signer: &LocalAccount,
+    receiver: AccountAddress,
+    overrides: Option<Overrides>,
+) -> AppResult<EntryFnArgs<'_>> {
+    let (mn, fun, args) = ("faucet", "fund", vec![receiver.to_bytes()?]);
+    let fn_args = EntryFnArgs::new(signer, AccountAddress::ONE, mn, fun, args, vec![])?;
+    Ok(fn_args.with_overrides(overrides))
+}
+
+pub(crate) fn transfer_args(
+    from: &LocalAccount,
+    to: AccountAddress,
+    amount: u128,
+    overrides: Option<Overrides>,
+) -> AppResult<EntryFnArgs<'_>> {
+    let args = vec![to.to_bytes()?, amount.to_bytes()?];
+    let (mn, fun, owner) = ("endless_account", "transfer", from);
+
+    let fn_args = EntryFnArgs::new(owner, AccountAddress::ONE, mn, fun, args, vec![])?;
+    Ok(fn_args.with_overrides(overrides))
+}
+
+pub(crate) fn transfer_token_args(
+    from: &LocalAccount,
+    to: AccountAddress,
+    token: Token,
+    amount: u128,
+    overrides: Option<Overrides>,
+) -> AppResult<EntryFnArgs<'_>> {
+    let args = vec![to.to_bytes()?, amount.to_bytes()?, token.to_bytes()?];
+    let (mn, fun, owner) = ("endless_account", "transfer_coins", from);
+    let t_args = vec!["0x1::fungible_asset::Metadata"];
+
+    let fn_args = EntryFnArgs::new(owner, AccountAddress::ONE, mn, fun, args, t_args)?;
+    Ok(fn_args.with_overrides(overrides))
+}
@@ -0,0 +1,223 @@
+use crate::client::EnhancedClient;
+use crate::client::account_client::{transfer_args, transfer_token_args};
+use crate::client::seq_num::SeqNumManager;
+use crate::client::types::{Token, WaitConfig};
+use base_infra::result::AppResult;
+use endless_sdk::helper_client::Overrides;
+use endless_sdk::move_types::account_address::AccountAddress;
+use endless_sdk::rest_client::{PendingTransaction, Transaction};
+use endless_sdk::types::LocalAccount;
+use futures::StreamExt;
+use tracing::warn;
+
+/// One recipient of a batch transfer, EDS when `token` is None
+#[derive(Debug, Clone, Copy)]
+pub struct TransferItem {
+    pub to: AccountAddress,
+    pub amount: u128,
+    pub token: Option<Token>,
+}
+
+impl TransferItem {
+    pub fn eds(to: AccountAddress, amount: u128) -> Self {
+        Self {
+            to,
+            amount,
+            token: None,
+        }
+    }
+
+    pub fn token(to: AccountAddress, token: Token, amount: u128) -> Self {
+        Self {
+            to,
+            amount,
+            token: Some(token),
+        }
+    }
+}
+
+/// Outcome of one batch transfer item
+#[derive(Debug, Clone)]
+pub struct TransferResult {
+    pub item: TransferItem,
+    /// None when the transaction was never submitted
+    pub hash: Option<String>,
+    pub gas_used: Option<u64>,
+    pub success: bool,
+    pub vm_status: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Per-recipient results of a batch transfer, in the order of the items
+#[derive(Debug, Clone)]
+pub struct BatchTransferReport {
+    pub results: Vec<TransferResult>,
+}
+
+impl BatchTransferReport {
+    pub fn succeeded(&self) -> impl Iterator<Item = &TransferResult> {
+        self.results.iter().filter(|res| res.success)
+    }
+
+    pub fn failed(&self) -> impl Iterator<Item = &TransferResult> {
+        self.results.iter().filter(|res| !res.success)
+    }
+
+    pub fn all_succeeded(&self) -> bool {
+        self.results.iter().all(|res| res.success)
+    }
+}
+
+#[async_trait::async_trait]
+pub trait BatchClientTrait {
+    /// Transfer EDS or tokens to every item, keeping at most `concurrency` transactions in flight.
+    ///
+    /// Sequence numbers come from the client's sequence number manager,
+    /// or a manager of this batch when the client has none, which then
+    /// leaves the local sequence number of `from` past the batch.
+    async fn batch_transfer(
+        &self,
+        from: &LocalAccount,
+        items: Vec<TransferItem>,
+        concurrency: usize,
+        overrides: Option<Overrides>,
+    ) -> BatchTransferReport;
+}
+
+#[async_trait::async_trait]
+impl BatchClientTrait for EnhancedClient {
+    async fn batch_transfer(
+        &self,
+        from: &LocalAccount,
+        items: Vec<TransferItem>,
+        concurrency: usize,
+        overrides: Option<Overrides>,
+    ) -> BatchTransferReport {
+        // waiting confirms or releases the numbers of the manager the client submits with
+        let temporary = self.seq_num_manager().is_none();
+        let client = if temporary {
+            self.clone().with_seq_num_manager(SeqNumManager::new())
+        } else {
+            self.clone()
+        };
+        let (client, overrides) = (&client, &overrides);
+
+        let results = futures::stream::iter(items)
+            .map(|item| client.batch_transfer_item(from, item, overrides.clone()))
+            .buffered(concurrency.max(1))
+            .collect()
+            .await;
+
+        // continue from the batch, the local sequence number of `from` did not move
+        if let Some(seq_nums) = client.seq_num_manager().filter(|_| temporary) {
+            match seq_nums.peek(client, from.address()).await {
+                Ok(seq_num) => from.set_sequence_number(seq_num),
+                Err(err) => warn!(
+                    "batch transfer left sequence number of {} stale: {err}",
+                    from.address()
+                ),
+            }
+        }
+        BatchTransferReport { results }
+    }
+}
+
+impl EnhancedClient {
+    /// Submit and wait with the client's sequence number manager
+    async fn batch_transfer_item(
+        &self,
+        from: &LocalAccount,
+        item: TransferItem,
+        overrides: Option<Overrides>,
+    ) -> TransferResult {
+        let pending_tx = match self.submit_transfer_item(from, item, overrides).await {
+            Ok(pending_tx) => pending_tx,
+            Err(err) => {
+                warn!("batch transfer to {} failed to submit: {err}", item.to);
+                return TransferResult::failed(item, None, err.to_string());
+            }
+        };
+
+        // a failed transaction is committed too, its vm status goes into the result
+        let committed = self.wait_for_committed_txn(&pending_tx, WaitConfig::default());
+        match committed.await {
+            Ok(txn) => TransferResult::committed(item, &pending_tx, txn.inner()),
+            Err(err) => {
+                warn!("batch transfer to {} failed to commit: {err}", item.to);
+                let hash = Some(pending_tx.hash.to_string());
+                TransferResult::failed(item, hash, err.to_string())
+            }
+        }
+    }
+
+    async fn submit_transfer_item(
+        &self,
+        from: &LocalAccount,
+        item: TransferItem,
+        overrides: Option<Overrides>,
+    ) -> AppResult<PendingTransaction> {
+        let (to, amount) = (item.to, item.amount);
+        let fn_args = match item.token {
+            Some(token) => transfer_token_args(from, to, token, amount, overrides)?,
+            None => transfer_args(from, to, amount, overrides)?,
+        };
+        Ok(self.submit_entry_fn(fn_args).await?.into_inner())
+    }
+}
+
+impl TransferResult {
+    fn committed(item: TransferItem, pending_tx: &PendingTransaction, txn: &Transaction) -> Self {
+        let info = txn.transaction_info().ok();
+        Self {
+            item,
+            hash: Some(pending_tx.hash.to_string()),
+            gas_used: info.map(|info| info.gas_used.0),
+            success: info.is_some_and(|info| info.success),
+            vm_status: info.map(|info| info.vm_status.clone()),
+            error: None,
+        }
+    }
+
+    fn failed(item: TransferItem, hash: Option<String>, error: String) -> Self {
+        Self {
+            item,
+            hash,
+            gas_used: None,
+            success: false,
+            vm_status: None,
+            error: Some(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_node::{Endpoint, MockNode, MockResponse, test_account};
+
+    #[tokio::test]
+    async fn batch_with_one_failed_transfer() {
+        let node = MockNode::start().await;
+        node.set_view("account::get_sequence_number", MockResponse::view(&3u64));
+        let rejection = MockResponse::vm_error("Invalid transaction: INVALID_AUTH_KEY", 2);
+        node.enqueue(Endpoint::Submit, rejection);
+        let from = test_account(0x11);
+
+        let items = (1..=3)
+            .map(|n| TransferItem::eds(AccountAddress::new([n; 32]), 5))
+            .collect();
+        let report = node.client().batch_transfer(&from, items, 1, None).await;
+
+        let success: Vec<bool> = report.results.iter().map(|res| res.success).collect();
+        assert_eq!(success, vec![false, true, true]);
+        assert!(report.results[0].hash.is_none() && report.results[0].error.is_some());
+        // the rejected number is handed to the next transfer
+        let seq_nums: Vec<u64> = node
+            .submitted_txns()
+            .iter()
+            .map(|txn| txn.sequence_number())
+            .collect();
+        assert_eq!(seq_nums, vec![3, 3, 4]);
+        assert_eq!(from.sequence_number(), 5);
+    }
+}
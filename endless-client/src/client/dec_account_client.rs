@@ -0,0 +1,157 @@
+use crate::client::EnhancedClient;
+use crate::client::account_client::AcctClientTrait;
+use crate::client::fa_client::FaClientTrait;
+use crate::client::types::{Owner, Token};
+use crate::error::EdsErr;
+use crate::sdk_ext::types::ViewFnArgs;
+use any_types::number::ToRsU128;
+use any_types::number::big_decimal::{DecimalConvertError, ToBigDec, big_decimal_to_u128};
+use base_infra::app_err;
+use base_infra::result::AppResult;
+use bigdecimal::BigDecimal;
+use endless_sdk::helper_client::Overrides;
+use endless_sdk::move_types::account_address::AccountAddress;
+use endless_sdk::rest_client::{PendingTransaction, Response};
+use endless_sdk::types::LocalAccount;
+
+/// `AcctClientTrait` balances and transfers in whole tokens, scaled by on-chain decimals
+#[async_trait::async_trait]
+pub trait DecAcctClientTrait {
+    /// Fungible asset metadata address of EDS, cached by this client
+    async fn eds_token(&self) -> AppResult<Token>;
+
+    async fn balance_of_dec(&self, owner: Owner) -> AppResult<BigDecimal>;
+
+    async fn token_balance_of_dec(&self, owner: Owner, token: Token) -> AppResult<BigDecimal>;
+
+    /// Refuses amounts with more decimal places than EDS supports
+    async fn transfer_dec(
+        &self,
+        from: &LocalAccount,
+        to: AccountAddress,
+        amount: &BigDecimal,
+        overrides: Option<Overrides>,
+    ) -> AppResult<Response<PendingTransaction>>;
+
+    /// Refuses amounts with more decimal places than `token` supports
+    async fn transfer_token_dec(
+        &self,
+        from: &LocalAccount,
+        to: AccountAddress,
+        token: Token,
+        amount: &BigDecimal,
+        overrides: Option<Overrides>,
+    ) -> AppResult<Response<PendingTransaction>>;
+}
+
+#[async_trait::async_trait]
+impl DecAcctClientTrait for EnhancedClient {
+    async fn eds_token(&self) -> AppResult<Token> {
+        let token = self.eds_token.get_or_try_init(|| async {
+            let (mn, fun) = ("endless_coin", "get_metadata");
+            let args = ViewFnArgs::new(AccountAddress::ONE, mn, fun, vec![], vec![])?;
+            let metadata: AccountAddress = self.view_fn(args, &EdsErr::EdsMetadata, None).await?;
+            AppResult::Ok(Token::new(&metadata))
+        });
+        token.await.copied()
+    }
+
+    async fn balance_of_dec(&self, owner: Owner) -> AppResult<BigDecimal> {
+        let decimals = self.fa_metadata(self.eds_token().await?).await?.decimals;
+        from_chain_amount(self.balance_of(owner).await?, decimals)
+    }
+
+    async fn token_balance_of_dec(&self, owner: Owner, token: Token) -> AppResult<BigDecimal> {
+        let decimals = self.fa_metadata(token).await?.decimals;
+        from_chain_amount(self.token_balance_of(owner, token).await?, decimals)
+    }
+
+    async fn transfer_dec(
+        &self,
+        from: &LocalAccount,
+        to: AccountAddress,
+        amount: &BigDecimal,
+        overrides: Option<Overrides>,
+    ) -> AppResult<Response<PendingTransaction>> {
+        let decimals = self.fa_metadata(self.eds_token().await?).await?.decimals;
+        let amount = to_chain_amount(amount, decimals)?;
+        self.transfer(from, to, amount, overrides).await
+    }
+
+    async fn transfer_token_dec(
+        &self,
+        from: &LocalAccount,
+        to: AccountAddress,
+        token: Token,
+        amount: &BigDecimal,
+        overrides: Option<Overrides>,
+    ) -> AppResult<Response<PendingTransaction>> {
+        let decimals = self.fa_metadata(token).await?.decimals;
+        let amount = to_chain_amount(amount, decimals)?;
+        self.transfer_token(from, to, token, amount, overrides)
+            .await
+    }
+}
+
+/// Raw on-chain amount -> whole tokens, 123456789 with 8 decimals -> 1.23456789
+pub fn from_chain_amount(amount: u128, decimals: u8) -> AppResult<BigDecimal> {
+    amount.to_big_dec(decimals)
+}
+
+/// Whole tokens -> raw on-chain amount, refusing more decimal places than `decimals`
+pub fn to_chain_amount(amount: &BigDecimal, decimals: u8) -> AppResult<u128> {
+    // 1.50 is as precise as 1.5
+    let amount = amount.normalized();
+    match big_decimal_to_u128(&amount, decimals as i64) {
+        Ok(amount) => Ok(amount.to_u128()),
+        Err(DecimalConvertError::FractionalPart) => Err(app_err!(
+            &EdsErr::AmountPrecision,
+            format!("{amount} with {decimals} decimals")
+        )),
+        Err(err) => Err(app_err!(&EdsErr::InvalidAmount, format!("{amount}: {err}"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_node::{Endpoint, MockNode, MockResponse};
+    use std::str::FromStr;
+
+    fn dec(s: &str) -> BigDecimal {
+        BigDecimal::from_str(s).unwrap()
+    }
+
+    #[tokio::test]
+    async fn eds_token_read_once() {
+        let node = MockNode::start().await;
+        let eds = AccountAddress::new([7; 32]);
+        node.set_view("endless_coin::get_metadata", MockResponse::view(&eds));
+
+        let client = node.client();
+        assert_eq!(AccountAddress::from(client.eds_token().await.unwrap()), eds);
+        assert_eq!(
+            AccountAddress::from(client.clone().eds_token().await.unwrap()),
+            eds
+        );
+        assert_eq!(node.requests_to(Endpoint::View).len(), 1);
+    }
+
+    #[test]
+    fn scales_by_decimals() {
+        assert_eq!(to_chain_amount(&dec("1.5"), 8).unwrap(), 150_000_000);
+        assert_eq!(to_chain_amount(&dec("100"), 6).unwrap(), 100_000_000);
+        assert_eq!(
+            to_chain_amount(&dec("1.50000000000"), 8).unwrap(),
+            150_000_000
+        );
+        assert_eq!(from_chain_amount(150_000_000, 8).unwrap(), dec("1.5"));
+    }
+
+    #[test]
+    fn refuses_excess_precision_and_invalid_amounts() {
+        assert!(to_chain_amount(&dec("0.000000001"), 8).is_err());
+        assert!(to_chain_amount(&dec("-1"), 8).is_err());
+        assert!(to_chain_amount(&dec("1e40"), 8).is_err());
+    }
+}
@@ -0,0 +1,143 @@
+use crate::client::EnhancedClient;
+use crate::client::resource_client::ResourceClientTrait;
+use crate::client::types::Token;
+use crate::error::EdsErr;
+use crate::sdk_ext::types::ViewFnArgs;
+use base_infra::result::AppResult;
+use endless_sdk::move_types::account_address::AccountAddress;
+use moka::future::Cache;
+use serde::{Deserialize, Serialize};
+
+const FA_METADATA: &str = "0x1::fungible_asset::Metadata";
+
+/// `0x1::fungible_asset::Metadata` of a token, fixed once the asset is created
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FaMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub icon_uri: String,
+    pub project_uri: String,
+}
+
+/// Metadata is fixed when the asset is created, so it is cached until evicted
+pub(crate) fn metadata_cache() -> Cache<AccountAddress, FaMetadata> {
+    Cache::builder().max_capacity(1024).build()
+}
+
+/// Bcs layout of `0x1::fungible_asset::Metadata`
+#[derive(Deserialize)]
+struct MetadataResource {
+    name: String,
+    symbol: String,
+    decimals: u8,
+    icon_uri: String,
+    project_uri: String,
+}
+
+#[async_trait::async_trait]
+pub trait FaClientTrait {
+    /// Name, symbol, decimals and uris of `token`, cached by this client
+    async fn fa_metadata(&self, token: Token) -> AppResult<FaMetadata>;
+
+    /// Metadata of `token` read from chain, replacing the cached one
+    async fn refresh_fa_metadata(&self, token: Token) -> AppResult<FaMetadata>;
+
+    /// Current supply of `token` read from chain, None when the supply is not tracked
+    async fn fa_supply(&self, token: Token) -> AppResult<Option<u128>>;
+
+    /// Maximum supply of `token` read from chain, None when the supply is unlimited
+    async fn fa_max_supply(&self, token: Token) -> AppResult<Option<u128>>;
+}
+
+#[async_trait::async_trait]
+impl FaClientTrait for EnhancedClient {
+    async fn fa_metadata(&self, token: Token) -> AppResult<FaMetadata> {
+        match self.fa_metadata.get(&token.into()).await {
+            Some(metadata) => Ok(metadata),
+            None => self.refresh_fa_metadata(token).await,
+        }
+    }
+
+    async fn refresh_fa_metadata(&self, token: Token) -> AppResult<FaMetadata> {
+        let address: AccountAddress = token.into();
+        // ResourceNotFound when the address is not a fungible asset
+        let resource: MetadataResource = self.get_account_resource(address, FA_METADATA).await?;
+
+        let metadata = FaMetadata {
+            name: resource.name,
+            symbol: resource.symbol,
+            decimals: resource.decimals,
+            icon_uri: resource.icon_uri,
+            project_uri: resource.project_uri,
+        };
+        self.fa_metadata.insert(address, metadata.clone()).await;
+        Ok(metadata)
+    }
+
+    async fn fa_supply(&self, token: Token) -> AppResult<Option<u128>> {
+        self.fa_supply_view(token, "supply").await
+    }
+
+    async fn fa_max_supply(&self, token: Token) -> AppResult<Option<u128>> {
+        self.fa_supply_view(token, "maximum").await
+    }
+}
+
+impl EnhancedClient {
+    async fn fa_supply_view(&self, token: Token, fun: &str) -> AppResult<Option<u128>> {
+        let (args, t_args) = (vec![token.to_bytes()?], vec![FA_METADATA]);
+        let args = ViewFnArgs::new(AccountAddress::ONE, "fungible_asset", fun, args, t_args)?;
+        let ext_msg = Some(format!("fungible_asset::{fun}"));
+        self.view_fn(args, &EdsErr::FaSupply, ext_msg).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_node::{Endpoint, MockNode, MockResponse};
+
+    /// Fungible asset at `[7; 32]` with its metadata
+    async fn serve_asset() -> (MockNode, Token) {
+        let node = MockNode::start().await;
+        let asset = AccountAddress::new([7; 32]);
+        let metadata = ("Endless Coin", "EDS", 8u8, "icon", "project");
+        let metadata = MockResponse::bcs(bcs::to_bytes(&metadata).unwrap());
+        node.set_resource(asset, FA_METADATA, metadata);
+        (node, Token::new(&asset))
+    }
+
+    #[tokio::test]
+    async fn metadata_cached_until_refreshed() {
+        let (node, token) = serve_asset().await;
+        let client = node.client();
+
+        let metadata = client.fa_metadata(token).await.unwrap();
+        assert_eq!((metadata.symbol.as_str(), metadata.decimals), ("EDS", 8));
+        assert_eq!(metadata.icon_uri, "icon");
+        client.fa_metadata(token).await.unwrap();
+        assert_eq!(node.requests_to(Endpoint::Resource).len(), 1);
+        // no supply read for the metadata
+        assert!(node.requests_to(Endpoint::View).is_empty());
+
+        client.refresh_fa_metadata(token).await.unwrap();
+        assert_eq!(node.requests_to(Endpoint::Resource).len(), 2);
+    }
+
+    #[tokio::test]
+    async fn supply_read_on_every_call() {
+        let (node, token) = serve_asset().await;
+        let client = node.client();
+        node.set_view("fungible_asset::maximum", MockResponse::view(&None::<u128>));
+
+        node.set_view("fungible_asset::supply", MockResponse::view(&Some(100u128)));
+        assert_eq!(client.fa_supply(token).await.unwrap(), Some(100));
+        node.set_view("fungible_asset::supply", MockResponse::view(&Some(150u128)));
+        assert_eq!(client.fa_supply(token).await.unwrap(), Some(150));
+        assert_eq!(client.fa_max_supply(token).await.unwrap(), None);
+
+        let view_fn = node.requests_to(Endpoint::View)[0].view_fn.clone().unwrap();
+        assert_eq!(view_fn.args, vec![token.to_bytes().unwrap()]);
+    }
+}
@@ -0,0 +1,106 @@
+use crate::client::dec_account_client::{from_chain_amount, to_chain_amount};
+use crate::error::EdsErr;
+use crate::sdk_ext::types::{GasPriceLevel, GasPrices};
+use base_infra::app_err;
+use base_infra::result::AppResult;
+use bigdecimal::BigDecimal;
+
+/// Gas unit price level and fee limits applied to every transaction a client submits
+#[derive(Debug, Clone, Default)]
+pub struct FeePolicy {
+    /// Level of the node's gas price estimate to pay
+    pub level: GasPriceLevel,
+    /// Refuse transactions priced above this gas unit price, by the estimate of `level`
+    /// or set with `EntryFnArgs::with_gas_unit_price`
+    pub max_gas_unit_price: Option<u64>,
+    /// Refuse transactions whose simulated fee, `gas_used * gas_unit_price`, is above this
+    /// many EDS. Transactions are simulated for it before submission, unless already simulated
+    /// as by `entry_fn_auto_gas_wait_txn`. `max_gas_amount` still caps the gas they may use
+    pub max_fee: Option<BigDecimal>,
+}
+
+impl FeePolicy {
+    pub fn new(level: GasPriceLevel) -> Self {
+        Self {
+            level,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_max_gas_unit_price(self, max_gas_unit_price: u64) -> Self {
+        Self {
+            max_gas_unit_price: Some(max_gas_unit_price),
+            ..self
+        }
+    }
+
+    pub fn with_max_fee(self, max_fee: BigDecimal) -> Self {
+        Self {
+            max_fee: Some(max_fee),
+            ..self
+        }
+    }
+
+    /// Gas unit price of `level`, refused above `max_gas_unit_price`
+    pub fn gas_unit_price(&self, prices: &GasPrices) -> AppResult<u64> {
+        let gas_unit_price = prices.at(self.level);
+        self.check_gas_unit_price(gas_unit_price)?;
+        Ok(gas_unit_price)
+    }
+
+    /// Refuse a gas unit price above `max_gas_unit_price`
+    pub fn check_gas_unit_price(&self, gas_unit_price: u64) -> AppResult<()> {
+        match self.max_gas_unit_price {
+            Some(max) if gas_unit_price > max => Err(app_err!(
+                &EdsErr::GasPriceOverPolicy,
+                format!("gas_unit_price[{gas_unit_price}] max[{max}]")
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Refuse a fee of `gas_used` at `gas_unit_price` above `max_fee`
+    pub fn check_fee(&self, gas_used: u64, gas_unit_price: u64, eds_decimals: u8) -> AppResult<()> {
+        let Some(max_fee) = &self.max_fee else {
+            return Ok(());
+        };
+        let fee = gas_used as u128 * gas_unit_price as u128;
+        if fee <= to_chain_amount(max_fee, eds_decimals)? {
+            return Ok(());
+        }
+        let fee = from_chain_amount(fee, eds_decimals)?;
+        Err(app_err!(
+            &EdsErr::FeeOverPolicy,
+            format!("fee[{fee}] max_fee[{max_fee}]")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn fee_policy_limits() {
+        let prices = GasPrices {
+            deprioritized: 100,
+            normal: 150,
+            prioritized: 300,
+        };
+        let policy = FeePolicy::new(GasPriceLevel::Prioritized);
+        assert_eq!(policy.gas_unit_price(&prices).unwrap(), 300);
+        let policy = policy.with_max_gas_unit_price(200);
+        assert!(policy.gas_unit_price(&prices).is_err());
+        let policy = FeePolicy::default().with_max_gas_unit_price(200);
+        assert_eq!(policy.gas_unit_price(&prices).unwrap(), 150);
+        assert!(policy.check_gas_unit_price(200).is_ok());
+        assert!(policy.check_gas_unit_price(201).is_err());
+
+        // 0.01 EDS with 8 decimals is 1_000_000
+        assert!(policy.check_fee(u64::MAX, 150, 8).is_ok());
+        let policy = policy.with_max_fee(BigDecimal::from_str("0.01").unwrap());
+        assert!(policy.check_fee(10_000, 100, 8).is_ok());
+        assert!(policy.check_fee(10_001, 100, 8).is_err());
+    }
+}
@@ -0,0 +1,426 @@
+use crate::client::EnhancedClient;
+use crate::client::txn_stream::{Involvement, NODE_MAX_PAGE_SIZE, TxnCursor};
+use crate::error::EdsErr;
+use base_infra::app_err;
+use base_infra::result::AppResult;
+use endless_sdk::move_types::account_address::AccountAddress;
+use endless_sdk::rest_client::Transaction;
+use endless_sdk::rest_client::endless_api_types::TransactionPayload;
+use serde::{Deserialize, Serialize};
+
+/// Compact view of a committed user transaction
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TxnSummary {
+    pub version: u64,
+    pub hash: String,
+    pub sender: AccountAddress,
+    pub sequence_number: u64,
+    /// `address::module::function` of entry function payloads
+    pub function: Option<String>,
+    pub success: bool,
+    pub vm_status: String,
+    pub gas_used: u64,
+    pub gas_unit_price: u64,
+    /// Microseconds since the unix epoch
+    pub timestamp: u64,
+}
+
+impl TxnSummary {
+    /// None for transactions other than user transactions
+    pub fn of(txn: &Transaction) -> Option<Self> {
+        let Transaction::UserTransaction(txn) = txn else {
+            return None;
+        };
+        let function = match &txn.request.payload {
+            TransactionPayload::EntryFunctionPayload(payload) => Some(payload.function.to_string()),
+            _ => None,
+        };
+
+        Some(Self {
+            version: txn.info.version.0,
+            hash: txn.info.hash.to_string(),
+            sender: *txn.request.sender.inner(),
+            sequence_number: txn.request.sequence_number.0,
+            function,
+            success: txn.info.success,
+            vm_status: txn.info.vm_status.clone(),
+            gas_used: txn.info.gas_used.0,
+            gas_unit_price: txn.request.gas_unit_price.0,
+            timestamp: txn.timestamp.0,
+        })
+    }
+}
+
+/// Filters and bounds of an account history query
+#[derive(Debug, Clone)]
+pub struct HistoryQuery {
+    /// First sequence number, only for sent transactions
+    pub start: Option<u64>,
+    /// Most transactions of a page, an empty page for 0
+    pub limit: usize,
+    /// Inclusive version range
+    pub from_version: Option<u64>,
+    pub to_version: Option<u64>,
+    pub success: Option<bool>,
+    /// Full `address::module::function` or `module::function`
+    pub function: Option<String>,
+    pub involvement: Option<Involvement>,
+    /// Most node pages read for one history page, bounds version scans of sparse accounts.
+    /// At least one page is read, so that every history page makes progress
+    pub max_node_pages: usize,
+}
+
+impl Default for HistoryQuery {
+    fn default() -> Self {
+        Self {
+            start: None,
+            limit: 100,
+            from_version: None,
+            to_version: None,
+            success: None,
+            function: None,
+            involvement: None,
+            max_node_pages: 100,
+        }
+    }
+}
+
+impl HistoryQuery {
+    pub fn with_start(self, start: u64, limit: usize) -> Self {
+        Self {
+            start: Some(start),
+            limit,
+            ..self
+        }
+    }
+
+    pub fn with_versions(self, from_version: Option<u64>, to_version: Option<u64>) -> Self {
+        Self {
+            from_version,
+            to_version,
+            ..self
+        }
+    }
+
+    pub fn with_success(self, success: Option<bool>) -> Self {
+        Self { success, ..self }
+    }
+
+    pub fn with_function(self, function: Option<String>) -> Self {
+        Self { function, ..self }
+    }
+
+    pub fn with_involvement(self, involvement: Option<Involvement>) -> Self {
+        Self {
+            involvement,
+            ..self
+        }
+    }
+
+    pub fn with_max_node_pages(self, max_node_pages: usize) -> Self {
+        Self {
+            max_node_pages,
+            ..self
+        }
+    }
+
+    /// Sent transactions are listed by sequence number from `start`, anything else by
+    /// scanning versions from `from_version`, which such a query must set
+    pub fn start_cursor(&self) -> AppResult<TxnCursor> {
+        match (self.involvement, self.start, self.from_version) {
+            (Some(Involvement::Sender), start, _) => {
+                Ok(TxnCursor::SequenceNumber(start.unwrap_or(0)))
+            }
+            (_, Some(_), _) => Err(app_err!(
+                &EdsErr::UnboundedHistoryQuery,
+                "start only pages sent transactions"
+            )),
+            (_, None, Some(from_version)) => Ok(TxnCursor::Version(from_version)),
+            (_, None, None) => Err(app_err!(
+                &EdsErr::UnboundedHistoryQuery,
+                "scanning versions needs from_version"
+            )),
+        }
+    }
+
+    pub fn matches(&self, summary: &TxnSummary, involvement: Involvement) -> bool {
+        let in_range = self.from_version.is_none_or(|from| summary.version >= from)
+            && self.to_version.is_none_or(|to| summary.version <= to);
+        let function = match (&self.function, &summary.function) {
+            (None, _) => true,
+            (Some(want), Some(function)) => {
+                function == want || function.ends_with(&format!("::{want}"))
+            }
+            (Some(_), None) => false,
+        };
+
+        in_range
+            && function
+            && self
+                .success
+                .is_none_or(|success| summary.success == success)
+            && self.involvement.is_none_or(|want| involvement == want)
+    }
+}
+
+/// One page of account history, `next` resumes the query where the page ended
+#[derive(Debug, Clone)]
+pub struct HistoryPage {
+    pub items: Vec<(TxnSummary, Involvement)>,
+    /// None once the account, the ledger or the version range is exhausted
+    pub next: Option<TxnCursor>,
+}
+
+#[async_trait::async_trait]
+pub trait HistoryClientTrait {
+    /// One page of the transactions of `account` matching `query`,
+    /// from `cursor`, or from `HistoryQuery::start_cursor` when None
+    async fn account_history(
+        &self,
+        account: AccountAddress,
+        query: &HistoryQuery,
+        cursor: Option<TxnCursor>,
+    ) -> AppResult<HistoryPage>;
+
+    /// Every transaction of `account` matching `query`, page by page
+    async fn account_history_all(
+        &self,
+        account: AccountAddress,
+        query: &HistoryQuery,
+    ) -> AppResult<Vec<(TxnSummary, Involvement)>>;
+}
+
+#[async_trait::async_trait]
+impl HistoryClientTrait for EnhancedClient {
+    async fn account_history(
+        &self,
+        account: AccountAddress,
+        query: &HistoryQuery,
+        cursor: Option<TxnCursor>,
+    ) -> AppResult<HistoryPage> {
+        let mut cursor = match cursor {
+            Some(cursor) => cursor,
+            None => query.start_cursor()?,
+        };
+        let mut items = vec![];
+        if query.limit == 0 {
+            return Ok(HistoryPage {
+                items,
+                next: Some(cursor),
+            });
+        }
+
+        // without a node page the cursor never moves and `account_history_all` never ends
+        for _ in 0..query.max_node_pages.max(1) {
+            let (page, next) = self
+                .account_txn_page(account, cursor, NODE_MAX_PAGE_SIZE)
+                .await?;
+            if next == cursor {
+                return Ok(HistoryPage { items, next: None });
+            }
+
+            for (txn, after) in page {
+                let Some(summary) = TxnSummary::of(&txn) else {
+                    continue;
+                };
+                if query.to_version.is_some_and(|to| summary.version > to) {
+                    return Ok(HistoryPage { items, next: None });
+                }
+
+                let Some(involvement) = Involvement::of(&txn, &account) else {
+                    continue;
+                };
+                if query.matches(&summary, involvement) {
+                    items.push((summary, involvement));
+                }
+                if items.len() >= query.limit {
+                    return Ok(HistoryPage {
+                        items,
+                        next: Some(after),
+                    });
+                }
+            }
+            cursor = next;
+        }
+
+        Ok(HistoryPage {
+            items,
+            next: Some(cursor),
+        })
+    }
+
+    async fn account_history_all(
+        &self,
+        account: AccountAddress,
+        query: &HistoryQuery,
+    ) -> AppResult<Vec<(TxnSummary, Involvement)>> {
+        let mut all = vec![];
+        if query.limit == 0 {
+            return Ok(all);
+        }
+        let mut cursor = None;
+        loop {
+            let page = self.account_history(account, query, cursor).await?;
+            all.extend(page.items);
+            match page.next {
+                Some(next) => cursor = Some(next),
+                None => return Ok(all),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::account_client::transfer_args;
+    use crate::client::types::Token;
+    use crate::mock_node::{MockNode, TxnOutcome, test_account};
+    use endless_sdk::types::LocalAccount;
+
+    /// Commit a transfer of `from` with `outcome`, returning its version
+    async fn commit(node: &MockNode, from: &LocalAccount, outcome: TxnOutcome) -> u64 {
+        let args = transfer_args(from, AccountAddress::ONE, 5, None).unwrap();
+        let txn = node
+            .client()
+            .rest_client()
+            .sign_entry_fun(args)
+            .await
+            .unwrap();
+        node.commit_txn(txn, outcome)
+    }
+
+    #[tokio::test]
+    async fn pages_sent_transactions() {
+        let node = MockNode::start().await;
+        let (client, from) = (node.client(), test_account(0x11));
+        for _ in 0..5 {
+            commit(&node, &from, TxnOutcome::default()).await;
+        }
+        let query = HistoryQuery::default()
+            .with_involvement(Some(Involvement::Sender))
+            .with_start(1, 2);
+
+        let page = client
+            .account_history(from.address(), &query, None)
+            .await
+            .unwrap();
+        let seq_nums: Vec<u64> = page
+            .items
+            .iter()
+            .map(|(txn, _)| txn.sequence_number)
+            .collect();
+        assert_eq!(seq_nums, vec![1, 2]);
+        assert_eq!(page.next, Some(TxnCursor::SequenceNumber(3)));
+
+        let page = client
+            .account_history(from.address(), &query, page.next)
+            .await
+            .unwrap();
+        let seq_nums: Vec<u64> = page
+            .items
+            .iter()
+            .map(|(txn, _)| txn.sequence_number)
+            .collect();
+        assert_eq!(seq_nums, vec![3, 4]);
+        let page = client
+            .account_history(from.address(), &query, page.next)
+            .await
+            .unwrap();
+        assert!(page.items.is_empty() && page.next.is_none());
+
+        let all = client
+            .account_history_all(from.address(), &query)
+            .await
+            .unwrap();
+        assert_eq!(all.len(), 4);
+        let one_page = query.clone().with_max_node_pages(0);
+        let all = client
+            .account_history_all(from.address(), &one_page)
+            .await
+            .unwrap();
+        assert_eq!(all.len(), 4);
+        let none = query.with_start(0, 0);
+        let page = client
+            .account_history(from.address(), &none, None)
+            .await
+            .unwrap();
+        assert!(page.items.is_empty());
+    }
+
+    #[tokio::test]
+    async fn receives_deposits_into_primary_store() {
+        let node = MockNode::start().await;
+        let client = node.client();
+        let (payer, receiver) = (test_account(0x11), test_account(0x22));
+        let asset = AccountAddress::new([7; 32]);
+        let store = Token::new(&asset).primary_store(&receiver.address());
+
+        let paid = commit(&node, &payer, TxnOutcome::deposit(store, asset, 5)).await;
+        let elsewhere = TxnOutcome::deposit(AccountAddress::new([3; 32]), asset, 5);
+        commit(&node, &payer, elsewhere).await;
+        let sent = commit(&node, &receiver, TxnOutcome::default()).await;
+
+        let query = HistoryQuery::default().with_versions(Some(paid), None);
+        let all = client
+            .account_history_all(receiver.address(), &query)
+            .await
+            .unwrap();
+        let found: Vec<(u64, Involvement)> = all.iter().map(|(txn, i)| (txn.version, *i)).collect();
+        assert_eq!(
+            found,
+            vec![(paid, Involvement::Receiver), (sent, Involvement::Sender)]
+        );
+
+        let received = query.with_involvement(Some(Involvement::Receiver));
+        let all = client
+            .account_history_all(receiver.address(), &received)
+            .await
+            .unwrap();
+        assert_eq!(all.len(), 1);
+
+        // a version scan without a lower bound would start at genesis
+        let unbounded = HistoryQuery::default().with_involvement(Some(Involvement::Receiver));
+        let res = client
+            .account_history(receiver.address(), &unbounded, None)
+            .await;
+        assert!(format!("{res:?}").contains("CLT012"));
+    }
+
+    fn summary(version: u64, function: &str, success: bool) -> TxnSummary {
+        TxnSummary {
+            version,
+            hash: String::new(),
+            sender: AccountAddress::ONE,
+            sequence_number: 0,
+            function: Some(function.to_string()),
+            success,
+            vm_status: String::new(),
+            gas_used: 0,
+            gas_unit_price: 0,
+            timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn history_query_filters() {
+        let query = HistoryQuery::default()
+            .with_versions(Some(10), Some(20))
+            .with_success(Some(true))
+            .with_function(Some("endless_account::transfer".to_string()));
+        let transfer = "0x1::endless_account::transfer";
+
+        assert!(query.matches(&summary(10, transfer, true), Involvement::Sender));
+        assert!(!query.matches(&summary(21, transfer, true), Involvement::Sender));
+        assert!(!query.matches(&summary(15, transfer, false), Involvement::Sender));
+        let other = "0x1::endless_account::transfer_coins";
+        assert!(!query.matches(&summary(15, other, true), Involvement::Sender));
+
+        let query = query.with_involvement(Some(Involvement::Receiver));
+        assert!(!query.matches(&summary(15, transfer, true), Involvement::Sender));
+        assert_eq!(query.start_cursor().unwrap(), TxnCursor::Version(10));
+        let unbounded = query.clone().with_versions(None, Some(20));
+        assert!(unbounded.start_cursor().is_err());
+        assert!(query.with_start(5, 10).start_cursor().is_err());
+    }
+}
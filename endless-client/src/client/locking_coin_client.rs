@@ -0,0 +1,256 @@
+use crate::client::EnhancedClient;
+use crate::client::types::{Owner, Token};
+use crate::error::EdsErr;
+use crate::sdk_ext::types::{EntryFnArgs, ViewFnArgs};
+use crate::utils::bcs_ext::BcsExt;
+use base_infra::result::AppResult;
+use endless_sdk::helper_client::Overrides;
+use endless_sdk::move_types::account_address::AccountAddress;
+use endless_sdk::rest_client::{PendingTransaction, Response};
+use endless_sdk::types::LocalAccount;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+
+const LOCKING_MODULE: &str = "locking_coin_ex";
+
+/// Amount of a locking plan unlocking at `epoch`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnlockAt {
+    pub epoch: u64,
+    pub amount: u128,
+}
+
+/// `locking_coin_ex::get_unlock_info` of a staker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnlockInfo {
+    pub address: AccountAddress,
+    /// Unlocked and not claimed yet
+    pub unlocked: u128,
+    pub unlock_list: Vec<UnlockAt>,
+}
+
+impl UnlockInfo {
+    /// Split the unlock list at `epoch`, the current epoch of `IndexData`
+    pub fn schedule_at(&self, epoch: u64) -> UnlockSchedule {
+        let (due, upcoming) = self.unlock_list.iter().partition(|at| at.epoch <= epoch);
+        UnlockSchedule {
+            epoch,
+            unlocked: self.unlocked,
+            due,
+            upcoming,
+        }
+    }
+}
+
+/// Unlock list of a staker projected against an epoch
+#[derive(Debug, Clone)]
+pub struct UnlockSchedule {
+    pub epoch: u64,
+    pub unlocked: u128,
+    /// Unlocks at or before `epoch`
+    pub due: Vec<UnlockAt>,
+    /// Unlocks after `epoch`, in list order
+    pub upcoming: Vec<UnlockAt>,
+}
+
+impl UnlockSchedule {
+    /// Amount still locked after `epoch`
+    pub fn locked(&self) -> u128 {
+        self.upcoming.iter().map(|at| at.amount).sum()
+    }
+
+    pub fn next_unlock(&self) -> Option<UnlockAt> {
+        self.upcoming.iter().min_by_key(|at| at.epoch).copied()
+    }
+}
+
+/// `0x1::locking_coin_ex`, tokens locked for a staker and unlocked by epoch
+#[async_trait::async_trait]
+pub trait LockingCoinClientTrait {
+    /// Unlocked amount of `token` that `staker` can claim
+    async fn unlocked_balance(&self, token: Token, staker: Owner) -> AppResult<u128>;
+
+    /// Amount of `token` still locked for `staker`
+    async fn staking_amount(&self, token: Token, staker: Owner) -> AppResult<u128>;
+
+    async fn unlock_info(&self, token: Token, staker: Owner) -> AppResult<UnlockInfo>;
+
+    async fn stakers_unlock_info(
+        &self,
+        token: Token,
+        stakers: Vec<AccountAddress>,
+    ) -> AppResult<Vec<UnlockInfo>>;
+
+    /// Unlock info of `staker` split at the current epoch of the chain
+    async fn unlock_schedule(&self, token: Token, staker: Owner) -> AppResult<UnlockSchedule>;
+
+    /// Withdraw `amount` of the unlocked balance of `staker` to its primary store
+    async fn claim(
+        &self,
+        staker: &LocalAccount,
+        token: Token,
+        amount: u128,
+        overrides: Option<Overrides>,
+    ) -> AppResult<Response<PendingTransaction>>;
+
+    /// Move `amount` of the locked balance of `staker` that reached its unlock epoch
+    /// to the unlocked balance
+    async fn unlock(
+        &self,
+        staker: &LocalAccount,
+        token: Token,
+        amount: u128,
+        overrides: Option<Overrides>,
+    ) -> AppResult<Response<PendingTransaction>>;
+}
+
+#[async_trait::async_trait]
+impl LockingCoinClientTrait for EnhancedClient {
+    async fn unlocked_balance(&self, token: Token, staker: Owner) -> AppResult<u128> {
+        let args = vec![token.to_bytes()?, staker.to_bytes()?];
+        self.locking_view("unlocked_balance", args).await
+    }
+
+    async fn staking_amount(&self, token: Token, staker: Owner) -> AppResult<u128> {
+        let args = vec![token.to_bytes()?, staker.to_bytes()?];
+        self.locking_view("staking_amount", args).await
+    }
+
+    async fn unlock_info(&self, token: Token, staker: Owner) -> AppResult<UnlockInfo> {
+        let args = vec![token.to_bytes()?, staker.to_bytes()?];
+        self.locking_view("get_unlock_info", args).await
+    }
+
+    async fn stakers_unlock_info(
+        &self,
+        token: Token,
+        stakers: Vec<AccountAddress>,
+    ) -> AppResult<Vec<UnlockInfo>> {
+        let args = vec![token.to_bytes()?, stakers.to_bytes()?];
+        self.locking_view("get_stakers_unlock_info", args).await
+    }
+
+    async fn unlock_schedule(&self, token: Token, staker: Owner) -> AppResult<UnlockSchedule> {
+        let info = self.unlock_info(token, staker).await?;
+        let epoch = self.get_index().await?.epoch;
+        Ok(info.schedule_at(epoch))
+    }
+
+    async fn claim(
+        &self,
+        staker: &LocalAccount,
+        token: Token,
+        amount: u128,
+        overrides: Option<Overrides>,
+    ) -> AppResult<Response<PendingTransaction>> {
+        let args = vec![token.to_bytes()?, amount.to_bytes()?];
+        self.locking_entry_fn(staker, "claim", args, overrides)
+            .await
+    }
+
+    async fn unlock(
+        &self,
+        staker: &LocalAccount,
+        token: Token,
+        amount: u128,
+        overrides: Option<Overrides>,
+    ) -> AppResult<Response<PendingTransaction>> {
+        let args = vec![token.to_bytes()?, amount.to_bytes()?];
+        self.locking_entry_fn(staker, "unlock", args, overrides)
+            .await
+    }
+}
+
+impl EnhancedClient {
+    async fn locking_view<T: DeserializeOwned + Debug>(
+        &self,
+        fun: &str,
+        args: Vec<Vec<u8>>,
+    ) -> AppResult<T> {
+        let args = ViewFnArgs::new(AccountAddress::ONE, LOCKING_MODULE, fun, args, vec![])?;
+        let ext_msg = Some(format!("{LOCKING_MODULE}::{fun}"));
+        self.view_fn(args, &EdsErr::LockingCoinView, ext_msg).await
+    }
+
+    async fn locking_entry_fn(
+        &self,
+        staker: &LocalAccount,
+        fun: &str,
+        args: Vec<Vec<u8>>,
+        overrides: Option<Overrides>,
+    ) -> AppResult<Response<PendingTransaction>> {
+        let mn = LOCKING_MODULE;
+        let fn_args = EntryFnArgs::new(staker, AccountAddress::ONE, mn, fun, args, vec![])?;
+        self.submit_entry_fn(fn_args.with_overrides(overrides))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_node::{MockNode, entry_fn, test_account};
+
+    #[tokio::test]
+    async fn claim_and_unlock_encode_token_and_amount() {
+        let node = MockNode::start().await;
+        let staker = test_account(0x11);
+        let eds = AccountAddress::new([7; 32]);
+        let client = node.client();
+
+        client
+            .unlock(&staker, Token::new(&eds), 300, None)
+            .await
+            .unwrap();
+        client
+            .claim(&staker, Token::new(&eds), u128::MAX, None)
+            .await
+            .unwrap();
+
+        let txns = node.submitted_txns();
+        for (txn, fun, amount) in [(&txns[0], "unlock", 300), (&txns[1], "claim", u128::MAX)] {
+            let called = entry_fn(txn.payload());
+            assert_eq!(txn.sender(), staker.address());
+            assert_eq!(called.module().address(), &AccountAddress::ONE);
+            assert_eq!(called.module().name().as_str(), LOCKING_MODULE);
+            assert_eq!(called.function().as_str(), fun);
+            assert_eq!(called.args()[0], eds.to_vec());
+            assert_eq!(called.args()[1], amount.to_le_bytes().to_vec());
+        }
+    }
+
+    #[test]
+    fn unlock_schedule_at_epoch() {
+        let info = UnlockInfo {
+            address: AccountAddress::ONE,
+            unlocked: 5,
+            unlock_list: vec![
+                UnlockAt {
+                    epoch: 10,
+                    amount: 100,
+                },
+                UnlockAt {
+                    epoch: 20,
+                    amount: 200,
+                },
+                UnlockAt {
+                    epoch: 30,
+                    amount: 300,
+                },
+            ],
+        };
+
+        let schedule = info.schedule_at(20);
+        assert_eq!(schedule.due.len(), 2);
+        assert_eq!(schedule.locked(), 300);
+        assert_eq!(
+            schedule.next_unlock(),
+            Some(UnlockAt {
+                epoch: 30,
+                amount: 300
+            })
+        );
+        assert_eq!(info.schedule_at(30).next_unlock(), None);
+    }
+}
@@ -1,37 +1,155 @@
+pub mod abi_client;
 pub mod account_client;
+pub mod batch_client;
+pub mod dec_account_client;
+pub mod fa_client;
+pub mod fee_policy;
+pub mod history_client;
+pub mod locking_coin_client;
+pub mod multisig_client;
+pub mod pool;
+pub mod resource_client;
+pub mod seq_num;
+pub mod simulation_client;
+pub mod staking_client;
+pub mod txn_stream;
 pub mod types;
 
-use crate::client::types::IndexData;
-use crate::error::EdsErr;
+use crate::client::fa_client::FaMetadata;
+use crate::client::fee_policy::FeePolicy;
+use crate::client::seq_num::SeqNumManager;
+use crate::client::types::{GasEstimateConfig, IndexData, Token, TxnStatus, WaitConfig};
+use crate::error::{EdsErr, is_err_code};
+use crate::sdk_ext::abort::ErrorMaps;
 use crate::sdk_ext::rest_client::RestClient;
-use crate::sdk_ext::types::{EntryFnArgs, ViewFnArgs};
-use base_infra::map_err;
+use crate::sdk_ext::retry::{NodeFailures, RetryPolicy};
+use crate::sdk_ext::types::{ChainIdCache, EntryFnArgs, ViewFnArgs};
 use base_infra::result::{AppResult, DynErrCode};
+use base_infra::{app_err, map_err};
 use endless_sdk::helper_client::Overrides;
+use endless_sdk::move_types::account_address::AccountAddress;
+use endless_sdk::rest_client::endless_api_types::UserTransaction;
+use endless_sdk::rest_client::error::RestError;
 use endless_sdk::rest_client::{Client, PendingTransaction, Response, Transaction};
+use endless_sdk::types::LocalAccount;
+use moka::future::Cache;
 use serde::de::DeserializeOwned;
 use std::fmt::Debug;
 use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::OnceCell;
 use tracing::info;
 use url::Url;
 
 #[derive(Clone)]
 pub struct EnhancedClient {
     client: Client,
+    retry: RetryPolicy,
+    seq_nums: Option<SeqNumManager>,
+    fee_payer: Option<Arc<LocalAccount>>,
+    fa_metadata: Cache<AccountAddress, FaMetadata>,
+    /// EDS metadata address, read once per node
+    eds_token: Arc<OnceCell<Token>>,
+    error_maps: Arc<ErrorMaps>,
+    fee_policy: Option<FeePolicy>,
+    chain_id: ChainIdCache,
 }
 
 impl EnhancedClient {
     pub fn new(node_url: Url) -> Self {
         Self {
             client: Client::new(node_url),
+            retry: RetryPolicy::none(),
+            seq_nums: None,
+            fee_payer: None,
+            fa_metadata: fa_client::metadata_cache(),
+            eds_token: Arc::new(OnceCell::new()),
+            error_maps: ErrorMaps::shared_framework(),
+            fee_policy: None,
+            chain_id: ChainIdCache::default(),
         }
     }
 
     pub fn new_with_url_str(node_url: &str) -> AppResult<Self> {
         let node_url = Url::from_str(node_url).map_err(map_err!(&EdsErr::InvalidNodeUrl))?;
-        Ok(Self {
-            client: Client::new(node_url),
-        })
+        Ok(Self::new(node_url))
+    }
+
+    /// Retry idempotent rest calls: views, simulations and signed transaction submissions
+    pub fn with_retry_policy(self, retry: RetryPolicy) -> Self {
+        Self { retry, ..self }
+    }
+
+    /// Record rest calls that fail by `is_node_failure` in `failures`
+    pub(crate) fn with_node_failures(self, failures: NodeFailures) -> Self {
+        let retry = self.retry.clone().with_node_failures(failures);
+        Self { retry, ..self }
+    }
+
+    /// Allocate sequence numbers of submitted transactions from `seq_nums`
+    /// instead of the signer's in-memory sequence number
+    pub fn with_seq_num_manager(self, seq_nums: SeqNumManager) -> Self {
+        Self {
+            seq_nums: Some(seq_nums),
+            ..self
+        }
+    }
+
+    pub fn seq_num_manager(&self) -> Option<&SeqNumManager> {
+        self.seq_nums.as_ref()
+    }
+
+    /// Sponsor every transaction of this client with `fee_payer`,
+    /// unless the entry function args carry their own fee payer
+    pub fn with_fee_payer(self, fee_payer: Arc<LocalAccount>) -> Self {
+        Self {
+            fee_payer: Some(fee_payer),
+            ..self
+        }
+    }
+
+    pub fn fee_payer(&self) -> Option<&LocalAccount> {
+        self.fee_payer.as_deref()
+    }
+
+    /// Price every submitted transaction by `fee_policy` and refuse the ones over its limits.
+    /// The price of `Overrides` is replaced, set one with `EntryFnArgs::with_gas_unit_price`
+    pub fn with_fee_policy(self, fee_policy: FeePolicy) -> Self {
+        Self {
+            fee_policy: Some(fee_policy),
+            ..self
+        }
+    }
+
+    pub fn fee_policy(&self) -> Option<&FeePolicy> {
+        self.fee_policy.as_ref()
+    }
+
+    /// Name move aborts with `error_maps` instead of the bundled framework ones,
+    /// start from a clone of `ErrorMaps::framework()` to keep those
+    pub fn with_error_maps(self, error_maps: ErrorMaps) -> Self {
+        Self {
+            error_maps: Arc::new(error_maps),
+            ..self
+        }
+    }
+
+    pub fn error_maps(&self) -> &ErrorMaps {
+        &self.error_maps
+    }
+
+    /// Vm status of a committed transaction, aborts named by the client's error maps
+    pub fn txn_status(&self, txn: &Transaction) -> TxnStatus {
+        TxnStatus::of_with(txn, &self.error_maps)
+    }
+
+    /// Vm status of a simulated transaction, aborts named by the client's error maps
+    pub fn simulated_status(&self, simulated: &UserTransaction) -> TxnStatus {
+        if simulated.info.success {
+            return TxnStatus::Success;
+        }
+        TxnStatus::parse_with(&simulated.info.vm_status, &self.error_maps)
     }
 
     pub fn get_client(&self) -> &Client {
@@ -40,15 +158,24 @@ impl EnhancedClient {
 
     pub fn rest_client(&self) -> RestClient<'_> {
         RestClient::new(&self.client)
+            .with_retry_policy(self.retry.clone())
+            .with_chain_id_cache(self.chain_id.clone())
+    }
+
+    /// Forget the cached chain id of the node, e.g. after it was redeployed with a new chain
+    pub fn invalidate_chain_id(&self) {
+        self.chain_id.invalidate();
     }
 
     pub async fn get_index(&self) -> AppResult<IndexData> {
         let res = self
-            .client
-            .get_index()
+            .retry
+            .run("get_index", || self.client.get_index())
             .await
             .map_err(map_err!(&EdsErr::GetVersionErr))?;
-        Ok(res.into())
+        let index: IndexData = res.into();
+        self.rest_client().check_chain_id(index.chain_id)?;
+        Ok(index)
     }
 
     pub async fn view_fn_with_err<T: DeserializeOwned + Debug>(
@@ -84,30 +211,440 @@ impl EnhancedClient {
         gas_used: Option<u64>,
     ) -> AppResult<Response<Transaction>> {
         let fn_name = args.fn_name.clone();
-        let mut overrides = None;
-        if let Some(gas_used) = gas_used {
-            let max_gas_amount = gas_used + 100;
-            info!("do entry_fn[{fn_name}] with max_gas_amount: {max_gas_amount}");
-            overrides = Some(Overrides {
-                max_gas_amount,
-                ..Overrides::default()
-            });
+        let fn_args = args.with_overrides(gas_used_overrides(&fn_name, gas_used));
+        let pending_tx = self.submit_entry_fn(fn_args).await?.into_inner();
+        // .map_err(map_err!(code))?
+        info!("entry_fn[{fn_name}] pending_tx_hash: {}", pending_tx.hash);
+        self.wait_for_txn(&pending_tx).await
+    }
+
+    /// Simulate with gas estimation, then submit with the simulated gas and
+    /// estimated gas unit price and wait for the transaction
+    pub async fn entry_fn_auto_gas_wait_txn(
+        &self,
+        args: EntryFnArgs<'_>,
+        gas_cfg: &GasEstimateConfig,
+    ) -> AppResult<Response<Transaction>> {
+        let fn_name = args.fn_name.clone();
+        let simulation_args = self.simulation_args(self.sponsored(args.clone())).await?;
+        let res = self
+            .rest_client()
+            .simulate_fun_with_estimation(simulation_args, true, true)
+            .await?;
+        let simulated = res.inner().first().ok_or_else(|| {
+            let msg = format!("function[{fn_name}] empty simulation result");
+            app_err!(&EdsErr::SimulateTxnErr, msg)
+        })?;
+
+        if !simulated.info.success {
+            let reason = match self.simulated_status(simulated) {
+                TxnStatus::MoveAbort(abort) => abort.to_string(),
+                _ => simulated.info.vm_status.clone(),
+            };
+            let msg = format!("function[{fn_name}] vm_status: {reason}");
+            return Err(app_err!(&EdsErr::SimulateVmFailed, msg));
         }
 
-        let fn_args = args.with_overrides(overrides);
-        let pending_tx = self.rest_client().entry_fun(fn_args).await?.into_inner();
-        // .map_err(map_err!(code))?
+        let gas_used = simulated.info.gas_used.0;
+        let max_gas_amount = gas_cfg.max_gas_amount(gas_used).ok_or_else(|| {
+            let msg = format!("function[{fn_name}] gas_used: {gas_used}");
+            app_err!(&EdsErr::GasExceedsCap, msg)
+        })?;
+        // the price estimated by the simulation, unless the caller or the fee policy sets one
+        let args = match (&self.fee_policy, args.gas_unit_price) {
+            (None, None) => args.with_gas_unit_price(Some(simulated.request.gas_unit_price.0)),
+            _ => args,
+        };
+        info!("do entry_fn[{fn_name}] with max_gas_amount: {max_gas_amount}");
+
+        let overrides = Overrides {
+            max_gas_amount,
+            ..args.overrides.clone().unwrap_or_default()
+        };
+        let fn_args = args.with_overrides(Some(overrides));
+        let pending_tx = self
+            .submit_simulated(fn_args, Some(gas_used))
+            .await?
+            .into_inner();
         info!("entry_fn[{fn_name}] pending_tx_hash: {}", pending_tx.hash);
         self.wait_for_txn(&pending_tx).await
     }
 
+    /// Apply the client's fee payer to `args` without a fee payer of their own
+    pub(crate) fn sponsored<'a>(&'a self, args: EntryFnArgs<'a>) -> EntryFnArgs<'a> {
+        match (args.fee_payer, self.fee_payer()) {
+            (None, Some(fee_payer)) => args.with_fee_payer(Some(fee_payer)),
+            _ => args,
+        }
+    }
+
+    /// Simulate at the sequence number the manager hands out next, if one is set,
+    /// so the simulation runs after the transactions in flight
+    async fn simulation_args<'a>(&self, args: EntryFnArgs<'a>) -> AppResult<EntryFnArgs<'a>> {
+        match (&self.seq_nums, args.sequence_number) {
+            (Some(seq_nums), None) => {
+                let seq_num = seq_nums.peek(self, args.signer.address()).await?;
+                Ok(args.with_sequence_number(Some(seq_num)))
+            }
+            _ => Ok(args),
+        }
+    }
+
+    /// Simulate the entry function, sponsored by the client's fee payer if one is set
+    pub async fn simulate_entry_fn(
+        &self,
+        args: EntryFnArgs<'_>,
+    ) -> AppResult<Response<Vec<UserTransaction>>> {
+        let args = self.simulation_args(self.sponsored(args)).await?;
+        self.rest_client().simulate_fun(args).await
+    }
+
+    /// Price `args` by the client's fee policy, see `with_fee_policy`
+    pub(crate) async fn priced<'a>(&self, args: EntryFnArgs<'a>) -> AppResult<EntryFnArgs<'a>> {
+        let Some(policy) = &self.fee_policy else {
+            return Ok(args);
+        };
+        let gas_unit_price = match args.gas_unit_price {
+            Some(gas_unit_price) => {
+                policy.check_gas_unit_price(gas_unit_price)?;
+                gas_unit_price
+            }
+            None => {
+                let prices = self.rest_client().estimate_gas_price().await?;
+                policy.gas_unit_price(&prices)?
+            }
+        };
+        info!(
+            "entry_fn[{}] priced by fee policy at gas_unit_price: {gas_unit_price}",
+            args.fn_name
+        );
+        Ok(args.with_gas_unit_price(Some(gas_unit_price)))
+    }
+
+    /// Refuse `args` if the fee of `gas_used` at its gas unit price is above the fee policy's
+    /// `max_fee`, simulating the transaction for its gas when `gas_used` is None
+    pub(crate) async fn check_fee(
+        &self,
+        args: &EntryFnArgs<'_>,
+        gas_used: Option<u64>,
+    ) -> AppResult<()> {
+        let Some(policy) = self.fee_policy.as_ref().filter(|p| p.max_fee.is_some()) else {
+            return Ok(());
+        };
+        let gas_used = match gas_used {
+            Some(gas_used) => gas_used,
+            None => {
+                let res = self.simulate_entry_fn(args.clone()).await?;
+                let simulated = res.inner().first().ok_or_else(|| {
+                    let msg = format!("function[{}] empty simulation result", args.fn_name);
+                    app_err!(&EdsErr::SimulateTxnErr, msg)
+                })?;
+                simulated.info.gas_used.0
+            }
+        };
+        let gas_unit_price = args.gas_overrides().unwrap_or_default().gas_unit_price;
+        policy.check_fee(gas_used, gas_unit_price, self.eds_decimals().await?)
+    }
+
+    /// Submit the entry function, through the sequence number manager if one is set,
+    /// priced and checked by the client's fee policy if one is set
+    pub async fn submit_entry_fn(
+        &self,
+        args: EntryFnArgs<'_>,
+    ) -> AppResult<Response<PendingTransaction>> {
+        self.submit_simulated(args, None).await
+    }
+
+    /// `submit_entry_fn` of a transaction simulated to use `gas_used`, if known
+    async fn submit_simulated(
+        &self,
+        args: EntryFnArgs<'_>,
+        gas_used: Option<u64>,
+    ) -> AppResult<Response<PendingTransaction>> {
+        let args = self.priced(self.sponsored(args)).await?;
+        self.check_fee(&args, gas_used).await?;
+        match &self.seq_nums {
+            Some(seq_nums) => seq_nums.submit(self, args).await,
+            None => self.rest_client().entry_fun(args).await,
+        }
+    }
+
+    /// Wait with the default `WaitConfig`, see `wait_for_txn_with`
     pub async fn wait_for_txn(
         &self,
         pending_tx: &PendingTransaction,
     ) -> AppResult<Response<Transaction>> {
-        self.client
-            .wait_for_transaction(pending_tx)
+        self.wait_for_txn_with(pending_tx, WaitConfig::default())
+            .await
+    }
+
+    /// Wait until the transaction is committed and succeeded,
+    /// a failed vm status is `TxnMoveAbort`, `TxnOutOfGas` or `TxnVmError`
+    pub async fn wait_for_txn_with(
+        &self,
+        pending_tx: &PendingTransaction,
+        config: WaitConfig,
+    ) -> AppResult<Response<Transaction>> {
+        let txn = self.wait_for_committed_txn(pending_tx, config).await?;
+        check_txn_status(txn.inner(), &self.error_maps)?;
+        Ok(txn)
+    }
+
+    /// Wait until the transaction is committed, whatever its vm status
+    pub async fn wait_for_committed_txn(
+        &self,
+        pending_tx: &PendingTransaction,
+        config: WaitConfig,
+    ) -> AppResult<Response<Transaction>> {
+        let res = self.poll_txn(pending_tx, config).await;
+        if let Some(seq_nums) = &self.seq_nums {
+            let sender = *pending_tx.request.sender.inner();
+            let seq_num = pending_tx.request.sequence_number.0;
+            // a committed transaction uses its sequence number even if it failed, after a
+            // timeout or a failed lookup it may still commit and stays in flight until a resync
+            match &res {
+                Ok(_) => seq_nums.confirm(sender, seq_num).await,
+                Err(err) if is_err_code(err, &EdsErr::TxnExpired) => {
+                    seq_nums.release(sender, seq_num).await
+                }
+                Err(_) => {}
+            }
+        }
+        res
+    }
+
+    async fn poll_txn(
+        &self,
+        pending_tx: &PendingTransaction,
+        config: WaitConfig,
+    ) -> AppResult<Response<Transaction>> {
+        let (hash, started) = (pending_tx.hash, Instant::now());
+        let expiration_usecs = pending_tx.request.expiration_timestamp_secs.0 * 1_000_000;
+
+        loop {
+            let res = self
+                .retry
+                .run("get_transaction_by_hash", || {
+                    self.client.get_transaction_by_hash(hash.into())
+                })
+                .await;
+            // not known to the node yet is 404, still in mempool is a pending transaction
+            let ledger_usecs = match res {
+                Ok(resp) if !matches!(resp.inner(), Transaction::PendingTransaction(_)) => {
+                    return Ok(resp);
+                }
+                Ok(resp) => Some(resp.state().timestamp_usecs),
+                Err(RestError::Api(resp)) if resp.status_code.as_u16() == 404 => {
+                    resp.state.map(|state| state.timestamp_usecs)
+                }
+                Err(err) => {
+                    return Err(err).map_err(map_err!(&EdsErr::WaitForTxnErr, hash.to_string()));
+                }
+            };
+
+            let expired = ledger_usecs.is_some_and(|now| now >= expiration_usecs);
+            if config.check_expiry && expired {
+                return Err(app_err!(&EdsErr::TxnExpired, format!("txn[{hash}]")));
+            }
+            if started.elapsed() >= config.timeout {
+                let msg = format!("txn[{hash}] after {:?}", config.timeout);
+                return Err(app_err!(&EdsErr::WaitTxnTimeout, msg));
+            }
+            tokio::time::sleep(config.poll_interval).await;
+        }
+    }
+}
+
+/// `Ok` for a successful transaction, otherwise the error of its vm status
+pub(crate) fn check_txn_status(txn: &Transaction, error_maps: &ErrorMaps) -> AppResult<()> {
+    let hash = txn.transaction_info().map(|info| info.hash.to_string());
+    let hash = hash.unwrap_or_default();
+    match TxnStatus::of_with(txn, error_maps) {
+        TxnStatus::Success => Ok(()),
+        TxnStatus::MoveAbort(abort) => Err(app_err!(
+            &EdsErr::TxnMoveAbort,
+            format!("txn[{hash}] {abort}")
+        )),
+        TxnStatus::OutOfGas => Err(app_err!(&EdsErr::TxnOutOfGas, format!("txn[{hash}]"))),
+        TxnStatus::VmError(vm_status) => Err(app_err!(
+            &EdsErr::TxnVmError,
+            format!("txn[{hash}] {vm_status}")
+        )),
+    }
+}
+
+fn gas_used_overrides(fn_name: &str, gas_used: Option<u64>) -> Option<Overrides> {
+    let max_gas_amount = gas_used? + 100;
+    info!("do entry_fn[{fn_name}] with max_gas_amount: {max_gas_amount}");
+    Some(Overrides {
+        max_gas_amount,
+        ..Overrides::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::account_client::{AcctClientTrait, transfer_args};
+    use crate::mock_node::{
+        Endpoint, MockLedger, MockNode, MockResponse, TxnOutcome, test_account,
+    };
+    use crate::sdk_ext::types::GasPriceLevel;
+    use bigdecimal::BigDecimal;
+    use endless_sdk::types::transaction::authenticator::TransactionAuthenticator;
+    use std::time::Duration;
+
+    fn assert_err_code<T: std::fmt::Debug>(res: AppResult<T>, code: &str) {
+        let err = format!("{:?}", res.unwrap_err());
+        assert!(err.contains(code), "expected {code}, got {err}");
+    }
+
+    #[tokio::test]
+    async fn wait_fails_with_typed_vm_status() {
+        let node = MockNode::start().await;
+        node.set_txn_outcome(TxnOutcome {
+            success: false,
+            vm_status: "Move abort in 0x1::endless_account: 0x10001".to_string(),
+            gas_used: 5,
+            ..TxnOutcome::default()
+        });
+
+        let (from, client) = (test_account(0x11), node.client());
+        let res = client.transfer_wait_txn(&from, AccountAddress::ONE, 5, None);
+        let res = res.await;
+        assert!(format!("{res:?}").contains("EACCOUNT_NOT_FOUND"));
+        assert_err_code(res, "TXN001");
+
+        node.set_txn_outcome(TxnOutcome {
+            success: false,
+            vm_status: "Out of gas".to_string(),
+            gas_used: 5,
+            ..TxnOutcome::default()
+        });
+        let res = client.transfer_wait_txn(&from, AccountAddress::ONE, 5, None);
+        assert_err_code(res.await, "TXN002");
+    }
+
+    #[tokio::test]
+    async fn wait_stops_on_expiry_and_timeout() {
+        let node = MockNode::start().await;
+        node.set_pending_polls(usize::MAX);
+        node.set_view("account::get_sequence_number", MockResponse::view(&7u64));
+        let seq_nums = SeqNumManager::new();
+        let from = test_account(0x11);
+        let client = node.client().with_seq_num_manager(seq_nums.clone());
+        let config = WaitConfig {
+            timeout: Duration::from_millis(50),
+            poll_interval: Duration::from_millis(10),
+            check_expiry: true,
+        };
+
+        let pending = client.transfer(&from, AccountAddress::ONE, 5, None);
+        let pending = pending.await.unwrap().into_inner();
+        let res = client.wait_for_txn_with(&pending, config).await;
+        assert_err_code(res, "TXN005");
+        // the transaction may still commit after a timeout
+        assert_eq!(seq_nums.in_flight(&from.address()).await, vec![7]);
+
+        // ledger time past the expiration of the pending transaction
+        let expiration = pending.request.expiration_timestamp_secs.0;
+        let ledger = node.ledger();
+        node.set_ledger(MockLedger {
+            timestamp_usecs: (expiration + 1) * 1_000_000,
+            ..ledger
+        });
+        let res = client.wait_for_txn_with(&pending, config).await;
+        assert_err_code(res, "TXN004");
+        assert!(seq_nums.in_flight(&from.address()).await.is_empty());
+        let next = client.transfer(&from, AccountAddress::ONE, 5, None);
+        next.await.unwrap();
+        assert_eq!(node.submitted_txns()[1].sequence_number(), 7);
+    }
+
+    #[tokio::test]
+    async fn fee_policy_prices_and_refuses() {
+        let node = MockNode::start().await;
+        let from = test_account(0x11);
+        let policy = FeePolicy::new(GasPriceLevel::Prioritized);
+        let client = node.client().with_fee_policy(policy.clone());
+
+        client
+            .transfer(&from, AccountAddress::ONE, 5, None)
             .await
-            .map_err(map_err!(&EdsErr::WaitForTxnErr))
+            .unwrap();
+        client
+            .transfer(&from, AccountAddress::ONE, 5, None)
+            .await
+            .unwrap();
+        let txns = node.submitted_txns();
+        assert!(txns.iter().all(|txn| txn.gas_unit_price() == 150));
+        // estimate reused within its ttl
+        assert_eq!(node.requests_to(Endpoint::GasPrice).len(), 1);
+
+        let client = client.with_fee_policy(policy.with_max_gas_unit_price(120));
+        let res = client.transfer(&from, AccountAddress::ONE, 5, None).await;
+        assert_err_code(res, "FEE001");
+        assert_eq!(node.submitted_txns().len(), 2);
+
+        // a price of the caller is kept below the policy limit
+        let priced = |gas_unit_price| {
+            let args = transfer_args(&from, AccountAddress::ONE, 5, None).unwrap();
+            args.with_gas_unit_price(Some(gas_unit_price))
+        };
+        client.submit_entry_fn(priced(110)).await.unwrap();
+        assert_eq!(node.submitted_txns()[2].gas_unit_price(), 110);
+        let res = client.submit_entry_fn(priced(130)).await;
+        assert_err_code(res, "FEE001");
+        assert_eq!(node.submitted_txns().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn fee_policy_refuses_simulated_fee() {
+        let node = MockNode::start().await;
+        node.serve_eds(AccountAddress::new([7; 32]));
+
+        // 0.01 EDS is 10_000 gas at the normal estimate of 100
+        let max_fee = BigDecimal::from_str("0.01").unwrap();
+        let policy = FeePolicy::new(GasPriceLevel::Normal).with_max_fee(max_fee);
+        let (from, client) = (test_account(0x11), node.client().with_fee_policy(policy));
+        let max_gas = Some(Overrides {
+            max_gas_amount: 1_000_000,
+            ..Overrides::default()
+        });
+        let using_gas = |gas_used| TxnOutcome {
+            gas_used,
+            ..TxnOutcome::default()
+        };
+
+        // max_gas_amount allows more, the fee is of the gas the transfer uses
+        node.set_txn_outcome(using_gas(10_000));
+        let res = client.transfer(&from, AccountAddress::ONE, 5, max_gas.clone());
+        res.await.unwrap();
+        node.set_txn_outcome(using_gas(10_001));
+        let res = client.transfer(&from, AccountAddress::ONE, 5, max_gas);
+        assert_err_code(res.await, "FEE002");
+        assert_eq!(node.submitted_txns().len(), 1);
+        assert_eq!(node.requests_to(Endpoint::Simulate).len(), 2);
+    }
+
+    #[tokio::test]
+    async fn sponsored_transfer_signed_by_fee_payer() {
+        let node = MockNode::start().await;
+        let (from, fee_payer) = (test_account(0x11), test_account(0x22));
+        let fee_payer_addr = fee_payer.address();
+        let client = node.client().with_fee_payer(Arc::new(fee_payer));
+
+        client
+            .transfer(&from, AccountAddress::ONE, 5, None)
+            .await
+            .unwrap();
+        let submitted = node.submitted_txns();
+        assert_eq!(submitted[0].sender(), from.address());
+        match submitted[0].authenticator() {
+            TransactionAuthenticator::FeePayer {
+                fee_payer_address, ..
+            } => assert_eq!(fee_payer_address, fee_payer_addr),
+            other => panic!("expected a fee payer authenticator, got {other:?}"),
+        }
     }
 }
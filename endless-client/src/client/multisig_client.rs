@@ -0,0 +1,370 @@
+use crate::client::EnhancedClient;
+use crate::error::EdsErr;
+use crate::sdk_ext::types::{EntryFnArgs, ViewFnArgs};
+use crate::utils::bcs_ext::BcsExt;
+use base_infra::result::AppResult;
+use endless_sdk::helper_client::Overrides;
+use endless_sdk::move_types::account_address::AccountAddress;
+use endless_sdk::rest_client::{PendingTransaction, Response};
+use endless_sdk::types::LocalAccount;
+use endless_sdk::types::transaction::MultisigTransactionPayload;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+
+const MULTISIG_MODULE: &str = "multisig_account";
+
+/// `0x1::multisig_account::MultisigTransaction`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MultisigTxn {
+    /// Full payload, None when only the hash was proposed
+    pub payload: Option<Vec<u8>>,
+    pub payload_hash: Option<Vec<u8>>,
+    /// Owner votes, true to approve and false to reject,
+    /// a `SimpleMap<address, bool>` on chain which has the bcs of a vector of pairs
+    pub votes: Vec<(AccountAddress, bool)>,
+    pub creator: AccountAddress,
+    pub creation_time_secs: u64,
+}
+
+impl MultisigTxn {
+    pub fn approvals(&self) -> usize {
+        self.votes.iter().filter(|(_, approved)| *approved).count()
+    }
+
+    pub fn rejections(&self) -> usize {
+        self.votes.iter().filter(|(_, approved)| !*approved).count()
+    }
+}
+
+/// Pending multisig transaction with its sequence number in the multisig account
+#[derive(Debug, Clone)]
+pub struct MultisigProposal {
+    pub sequence_number: u64,
+    pub txn: MultisigTxn,
+}
+
+#[async_trait::async_trait]
+pub trait MultisigClientTrait {
+    /// Address of the multisig account `creator` creates next
+    async fn next_multisig_address(&self, creator: AccountAddress) -> AppResult<AccountAddress>;
+
+    /// Create a multisig account owned by `creator` and `additional_owners`,
+    /// executing transactions once `threshold` owners approve
+    async fn create_multisig_account(
+        &self,
+        creator: &LocalAccount,
+        additional_owners: Vec<AccountAddress>,
+        threshold: u64,
+        overrides: Option<Overrides>,
+    ) -> AppResult<Response<PendingTransaction>>;
+
+    /// Propose the entry function of `args` for the multisig account, signed by `args.signer`
+    async fn propose(
+        &self,
+        multisig: AccountAddress,
+        args: EntryFnArgs<'_>,
+    ) -> AppResult<Response<PendingTransaction>>;
+
+    async fn approve(
+        &self,
+        owner: &LocalAccount,
+        multisig: AccountAddress,
+        seq_num: u64,
+        overrides: Option<Overrides>,
+    ) -> AppResult<Response<PendingTransaction>>;
+
+    async fn reject(
+        &self,
+        owner: &LocalAccount,
+        multisig: AccountAddress,
+        seq_num: u64,
+        overrides: Option<Overrides>,
+    ) -> AppResult<Response<PendingTransaction>>;
+
+    /// Execute the next pending transaction of the multisig account,
+    /// `args` must carry the same entry function as the proposal
+    async fn execute(
+        &self,
+        multisig: AccountAddress,
+        args: EntryFnArgs<'_>,
+    ) -> AppResult<Response<PendingTransaction>>;
+
+    /// Transactions proposed but neither executed nor rejected, oldest first
+    async fn pending_proposals(&self, multisig: AccountAddress)
+    -> AppResult<Vec<MultisigProposal>>;
+
+    /// Whether the transaction of `seq_num` is next and has enough approvals
+    async fn can_be_executed(&self, multisig: AccountAddress, seq_num: u64) -> AppResult<bool>;
+
+    async fn owners(&self, multisig: AccountAddress) -> AppResult<Vec<AccountAddress>>;
+
+    async fn num_signatures_required(&self, multisig: AccountAddress) -> AppResult<u64>;
+}
+
+#[async_trait::async_trait]
+impl MultisigClientTrait for EnhancedClient {
+    async fn next_multisig_address(&self, creator: AccountAddress) -> AppResult<AccountAddress> {
+        let args = vec![creator.to_bytes()?];
+        self.multisig_view("get_next_multisig_account_address", args)
+            .await
+    }
+
+    async fn create_multisig_account(
+        &self,
+        creator: &LocalAccount,
+        additional_owners: Vec<AccountAddress>,
+        threshold: u64,
+        overrides: Option<Overrides>,
+    ) -> AppResult<Response<PendingTransaction>> {
+        let (metadata_keys, metadata_values) = (Vec::<String>::new(), Vec::<Vec<u8>>::new());
+        let args = vec![
+            additional_owners.to_bytes()?,
+            threshold.to_bytes()?,
+            metadata_keys.to_bytes()?,
+            metadata_values.to_bytes()?,
+        ];
+        let (mn, fun) = (MULTISIG_MODULE, "create_with_owners");
+
+        let fn_args = EntryFnArgs::new(creator, AccountAddress::ONE, mn, fun, args, vec![])?;
+        self.submit_entry_fn(fn_args.with_overrides(overrides))
+            .await
+    }
+
+    async fn propose(
+        &self,
+        multisig: AccountAddress,
+        args: EntryFnArgs<'_>,
+    ) -> AppResult<Response<PendingTransaction>> {
+        // the payload argument is a vector<u8> holding the bcs of the multisig payload
+        let payload = MultisigTransactionPayload::EntryFunction(args.entry_fn).to_bytes()?;
+        let (signer, fn_args) = (args.signer, vec![multisig.to_bytes()?, payload.to_bytes()?]);
+        let (mn, fun) = (MULTISIG_MODULE, "create_transaction");
+
+        let fn_args = EntryFnArgs::new(signer, AccountAddress::ONE, mn, fun, fn_args, vec![])?;
+        self.submit_entry_fn(fn_args.with_overrides(args.overrides))
+            .await
+    }
+
+    async fn approve(
+        &self,
+        owner: &LocalAccount,
+        multisig: AccountAddress,
+        seq_num: u64,
+        overrides: Option<Overrides>,
+    ) -> AppResult<Response<PendingTransaction>> {
+        self.vote(owner, multisig, seq_num, "approve_transaction", overrides)
+            .await
+    }
+
+    async fn reject(
+        &self,
+        owner: &LocalAccount,
+        multisig: AccountAddress,
+        seq_num: u64,
+        overrides: Option<Overrides>,
+    ) -> AppResult<Response<PendingTransaction>> {
+        self.vote(owner, multisig, seq_num, "reject_transaction", overrides)
+            .await
+    }
+
+    async fn execute(
+        &self,
+        multisig: AccountAddress,
+        args: EntryFnArgs<'_>,
+    ) -> AppResult<Response<PendingTransaction>> {
+        self.submit_entry_fn(args.with_multisig_address(Some(multisig)))
+            .await
+    }
+
+    async fn pending_proposals(
+        &self,
+        multisig: AccountAddress,
+    ) -> AppResult<Vec<MultisigProposal>> {
+        let args = vec![multisig.to_bytes()?];
+        let resp = self
+            .multisig_view_at("last_resolved_sequence_number", args.clone(), None)
+            .await?;
+        // both read at one ledger version, so that the numbers match the transactions
+        let version = resp.state().version;
+        let (_, last_resolved): (u8, u64) = resp.into_inner();
+        let fun = "get_pending_transactions";
+        let resp = self.multisig_view_at(fun, args, Some(version)).await?;
+        let (_, txns): (u8, Vec<MultisigTxn>) = resp.into_inner();
+
+        let proposals = (last_resolved + 1..).zip(txns);
+        Ok(proposals
+            .map(|(sequence_number, txn)| MultisigProposal {
+                sequence_number,
+                txn,
+            })
+            .collect())
+    }
+
+    async fn can_be_executed(&self, multisig: AccountAddress, seq_num: u64) -> AppResult<bool> {
+        let args = vec![multisig.to_bytes()?, seq_num.to_bytes()?];
+        self.multisig_view("can_be_executed", args).await
+    }
+
+    async fn owners(&self, multisig: AccountAddress) -> AppResult<Vec<AccountAddress>> {
+        self.multisig_view("owners", vec![multisig.to_bytes()?])
+            .await
+    }
+
+    async fn num_signatures_required(&self, multisig: AccountAddress) -> AppResult<u64> {
+        let args = vec![multisig.to_bytes()?];
+        self.multisig_view("num_signatures_required", args).await
+    }
+}
+
+impl EnhancedClient {
+    async fn vote(
+        &self,
+        owner: &LocalAccount,
+        multisig: AccountAddress,
+        seq_num: u64,
+        fun: &str,
+        overrides: Option<Overrides>,
+    ) -> AppResult<Response<PendingTransaction>> {
+        let args = vec![multisig.to_bytes()?, seq_num.to_bytes()?];
+        let mn = MULTISIG_MODULE;
+        let fn_args = EntryFnArgs::new(owner, AccountAddress::ONE, mn, fun, args, vec![])?;
+        self.submit_entry_fn(fn_args.with_overrides(overrides))
+            .await
+    }
+
+    async fn multisig_view<T: DeserializeOwned + Debug>(
+        &self,
+        fun: &str,
+        args: Vec<Vec<u8>>,
+    ) -> AppResult<T> {
+        let resp = self.multisig_view_at(fun, args, None).await?;
+        let (_, inner): (u8, T) = resp.into_inner();
+        Ok(inner)
+    }
+
+    /// View at `version`, the latest if None, with the ledger state it was read at
+    async fn multisig_view_at<T: DeserializeOwned + Debug>(
+        &self,
+        fun: &str,
+        args: Vec<Vec<u8>>,
+        version: Option<u64>,
+    ) -> AppResult<Response<(u8, T)>> {
+        let args = ViewFnArgs::new(AccountAddress::ONE, MULTISIG_MODULE, fun, args, vec![])?;
+        let ext_msg = Some(format!("{MULTISIG_MODULE}::{fun}"));
+        let args = args.with_ledger_version(version);
+        self.view_fn_with_err(args, &EdsErr::MultisigView, ext_msg)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::account_client::transfer_args;
+    use crate::mock_node::{Endpoint, MockNode, MockResponse, entry_fn, test_account};
+    use endless_sdk::types::transaction::TransactionPayload;
+
+    /// `0x1::multisig_account::MultisigTransaction` as laid out on chain,
+    /// `Option` as a vector of at most one element and `SimpleMap` as a vector of entries
+    #[derive(Serialize)]
+    struct OnChainMultisigTxn {
+        payload: Vec<Vec<u8>>,
+        payload_hash: Vec<Vec<u8>>,
+        votes: Vec<(AccountAddress, bool)>,
+        creator: AccountAddress,
+        creation_time_secs: u64,
+    }
+
+    #[tokio::test]
+    async fn propose_approve_execute() {
+        let node = MockNode::start().await;
+        let (first, second) = (test_account(0x11), test_account(0x22));
+        let multisig = AccountAddress::new([9; 32]);
+        let client = node.client();
+
+        let transfer = transfer_args(&first, AccountAddress::ONE, 5, None).unwrap();
+        let proposed = transfer.entry_fn.clone();
+        client.propose(multisig, transfer).await.unwrap();
+        client.approve(&second, multisig, 1, None).await.unwrap();
+        let transfer = transfer_args(&second, AccountAddress::ONE, 5, None).unwrap();
+        client.execute(multisig, transfer).await.unwrap();
+
+        let txns = node.submitted_txns();
+        let create = entry_fn(txns[0].payload());
+        assert_eq!(create.function().as_str(), "create_transaction");
+        assert_eq!(create.args()[0], multisig.to_bytes().unwrap());
+        let payload: Vec<u8> = bcs::from_bytes(&create.args()[1]).unwrap();
+        let payload: MultisigTransactionPayload = bcs::from_bytes(&payload).unwrap();
+        assert_eq!(
+            payload,
+            MultisigTransactionPayload::EntryFunction(proposed.clone())
+        );
+
+        let approve = entry_fn(txns[1].payload());
+        assert_eq!(txns[1].sender(), second.address());
+        assert_eq!(approve.function().as_str(), "approve_transaction");
+        assert_eq!(approve.args()[1], 1u64.to_bytes().unwrap());
+
+        match txns[2].payload() {
+            TransactionPayload::Multisig(executed) => {
+                assert_eq!(executed.multisig_address, multisig);
+                let executed = executed.transaction_payload.clone();
+                assert_eq!(
+                    executed,
+                    Some(MultisigTransactionPayload::EntryFunction(proposed))
+                );
+            }
+            other => panic!("expected a multisig payload, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn pending_proposals_from_on_chain_layout() {
+        let node = MockNode::start().await;
+        let (first, second) = (test_account(0x11).address(), test_account(0x22).address());
+        let pending = vec![
+            OnChainMultisigTxn {
+                payload: vec![vec![1, 2, 3]],
+                payload_hash: vec![],
+                votes: vec![(first, true), (second, false)],
+                creator: first,
+                creation_time_secs: 100,
+            },
+            OnChainMultisigTxn {
+                payload: vec![],
+                payload_hash: vec![vec![7; 32]],
+                votes: vec![(second, true)],
+                creator: second,
+                creation_time_secs: 200,
+            },
+        ];
+        let view = |fun: &str| format!("{MULTISIG_MODULE}::{fun}");
+        node.set_view(
+            &view("last_resolved_sequence_number"),
+            MockResponse::view(&4u64),
+        );
+        node.set_view(
+            &view("get_pending_transactions"),
+            MockResponse::view(&pending),
+        );
+
+        let multisig = AccountAddress::new([9; 32]);
+        let proposals = node.client().pending_proposals(multisig).await.unwrap();
+        let seq_nums: Vec<u64> = proposals.iter().map(|p| p.sequence_number).collect();
+        assert_eq!(seq_nums, vec![5, 6]);
+        // the pending transactions read at the version of the last resolved number
+        let views = node.requests_to(Endpoint::View);
+        assert_eq!(views[0].query, None);
+        let version = node.ledger().version;
+        assert_eq!(views[1].query, Some(format!("ledger_version={version}")));
+
+        let (proposed, hashed) = (&proposals[0].txn, &proposals[1].txn);
+        assert_eq!(proposed.payload, Some(vec![1, 2, 3]));
+        assert!(proposed.payload_hash.is_none());
+        assert_eq!((proposed.approvals(), proposed.rejections()), (1, 1));
+        assert!(hashed.payload.is_none());
+        assert_eq!(hashed.payload_hash, Some(vec![7; 32]));
+        assert_eq!((hashed.creator, hashed.creation_time_secs), (second, 200));
+    }
+}
@@ -0,0 +1,594 @@
+use crate::client::account_client::{
+    AcctClientTrait, faucet_args, transfer_args, transfer_token_args,
+};
+use crate::client::types::{IndexData, Owner, Token, WaitConfig};
+use crate::client::{EnhancedClient, check_txn_status};
+use crate::error::EdsErr;
+use crate::sdk_ext::abort::ErrorMaps;
+use crate::sdk_ext::account::LocalAccountExt;
+use crate::sdk_ext::retry::NodeFailures;
+use crate::sdk_ext::types::{EntryFnArgs, ViewFnArgs};
+use base_infra::result::{AppResult, DynErrCode};
+use base_infra::{app_err, map_err};
+use endless_sdk::helper_client::Overrides;
+use endless_sdk::move_types::account_address::AccountAddress;
+use endless_sdk::rest_client::endless_api_types::UserTransaction;
+use endless_sdk::rest_client::{PendingTransaction, Response, Transaction};
+use endless_sdk::types::LocalAccount;
+use futures::future::join_all;
+use serde::de::DeserializeOwned;
+use std::cmp::Reverse;
+use std::fmt::Debug;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Ledger versions a node may trail the most up-to-date node by before it is skipped
+const DEFAULT_MAX_LAG: u64 = 1_000;
+const DEFAULT_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Client pool over several nodes of one network.
+///
+/// Calls go to the most up-to-date healthy node by `ledger_version` and fail over to the
+/// next node on errors. Reads are repeated on the next node, transactions are signed once
+/// and only the signed transaction is resubmitted.
+///
+/// The pool has `AcctClientTrait` and the index, view, submit and wait calls of
+/// `EnhancedClient`. Any other client call fails over through `failover`.
+#[derive(Clone)]
+pub struct ClientPool {
+    nodes: Vec<EnhancedClient>,
+    health: Arc<RwLock<PoolHealth>>,
+    /// Held while re-checking stale health, so concurrent callers check once
+    refresh: Arc<tokio::sync::Mutex<()>>,
+    max_lag: u64,
+    check_interval: Duration,
+    /// Names the aborts of transactions waited for through the pool
+    error_maps: Arc<ErrorMaps>,
+}
+
+impl ClientPool {
+    pub fn new(nodes: Vec<EnhancedClient>) -> AppResult<Self> {
+        if nodes.is_empty() {
+            return Err(app_err!(&EdsErr::EmptyClientPool));
+        }
+
+        let health = PoolHealth {
+            versions: vec![None; nodes.len()],
+            checked_at: None,
+            chain_id: None,
+        };
+        Ok(Self {
+            nodes,
+            health: Arc::new(RwLock::new(health)),
+            refresh: Arc::new(tokio::sync::Mutex::new(())),
+            max_lag: DEFAULT_MAX_LAG,
+            check_interval: DEFAULT_CHECK_INTERVAL,
+            error_maps: ErrorMaps::shared_framework(),
+        })
+    }
+
+    /// Build the pool and check that its reachable nodes report one chain id,
+    /// nodes of another chain are left out of later health checks
+    pub async fn connect(nodes: Vec<EnhancedClient>) -> AppResult<Self> {
+        let pool = Self::new(nodes)?;
+        let indexes = join_all(pool.nodes.iter().map(|node| node.get_index())).await;
+        let mut chain_ids = indexes
+            .iter()
+            .zip(&pool.nodes)
+            .filter_map(|(res, node)| Some((res.as_ref().ok()?.chain_id, node)));
+
+        if let Some((chain_id, first)) = chain_ids.next() {
+            if let Some((other, node)) = chain_ids.find(|(other, _)| *other != chain_id) {
+                let (first_url, node_url) = (
+                    first.rest_client().node_url(),
+                    node.rest_client().node_url(),
+                );
+                let msg = format!(
+                    "node[{first_url}] chain_id[{chain_id}], node[{node_url}] chain_id[{other}]"
+                );
+                return Err(app_err!(&EdsErr::ChainIdMismatch, msg));
+            }
+            pool.health.write().expect("pool health lock").chain_id = Some(chain_id);
+        }
+        pool.health_check().await;
+        Ok(pool)
+    }
+
+    pub fn new_with_url_strs(node_urls: &[&str]) -> AppResult<Self> {
+        let nodes = node_urls
+            .iter()
+            .map(|url| EnhancedClient::new_with_url_str(url))
+            .collect::<AppResult<Vec<_>>>()?;
+        Self::new(nodes)
+    }
+
+    pub fn with_max_lag(self, max_lag: u64) -> Self {
+        Self { max_lag, ..self }
+    }
+
+    pub fn with_check_interval(self, check_interval: Duration) -> Self {
+        Self {
+            check_interval,
+            ..self
+        }
+    }
+
+    /// Name move aborts with `error_maps` instead of the bundled framework ones,
+    /// start from a clone of `ErrorMaps::framework()` to keep those
+    pub fn with_error_maps(self, error_maps: ErrorMaps) -> Self {
+        Self {
+            error_maps: Arc::new(error_maps),
+            ..self
+        }
+    }
+
+    pub fn error_maps(&self) -> &ErrorMaps {
+        &self.error_maps
+    }
+
+    /// Configure every node, e.g. to share one retry policy or sequence number manager
+    pub fn map_nodes(self, f: impl Fn(EnhancedClient) -> EnhancedClient) -> Self {
+        let nodes = self.nodes.into_iter().map(f).collect();
+        Self { nodes, ..self }
+    }
+
+    pub fn nodes(&self) -> &[EnhancedClient] {
+        &self.nodes
+    }
+
+    /// Check every node with `get_index`, None for unreachable nodes
+    /// and nodes of another chain than the pool's
+    pub async fn health_check(&self) -> Vec<Option<u64>> {
+        let indexes = join_all(self.nodes.iter().map(|node| node.get_index())).await;
+        let mut chain_id = self.health.read().expect("pool health lock").chain_id;
+        let versions: Vec<Option<u64>> = indexes
+            .into_iter()
+            .zip(&self.nodes)
+            .map(|(res, node)| {
+                let node_url = node.rest_client().node_url();
+                match res {
+                    Ok(index) if *chain_id.get_or_insert(index.chain_id) != index.chain_id => {
+                        warn!("node[{node_url}] is on chain_id[{}]", index.chain_id);
+                        None
+                    }
+                    Ok(index) => Some(index.ledger_version),
+                    Err(err) => {
+                        warn!("node[{node_url}] health check failed: {err}");
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        let mut health = self.health.write().expect("pool health lock");
+        health.chain_id = chain_id;
+        health.versions = versions.clone();
+        health.checked_at = Some(Instant::now());
+        versions
+    }
+
+    /// The most up-to-date healthy node
+    pub async fn best(&self) -> EnhancedClient {
+        let idx = self.ranked().await[0];
+        self.nodes[idx].clone()
+    }
+
+    /// Run `op` on nodes by preference until one of them succeeds.
+    ///
+    /// `op` fails over when one of its rest calls failed by `is_node_failure`, even if a
+    /// later call succeeded. Any other error, such as a view abort or a rejected
+    /// transaction, is returned as is. Calls the pool has no method for go through here:
+    ///
+    /// ```ignore
+    /// let simulated = pool.failover(|node| {
+    ///     let args = args.clone();
+    ///     async move { node.rest_client().simulate_fun(args).await }
+    /// });
+    /// ```
+    pub async fn failover<T, F, Fut>(&self, op: F) -> AppResult<T>
+    where
+        F: Fn(EnhancedClient) -> Fut,
+        Fut: Future<Output = AppResult<T>>,
+    {
+        let mut last_err = None;
+        for idx in self.ranked().await {
+            // every rest call of `op` on this node is recorded, not only the last one
+            let failures = NodeFailures::default();
+            let node = self.nodes[idx].clone().with_node_failures(failures.clone());
+            match op(node).await {
+                Ok(res) => return Ok(res),
+                Err(err) if !failures.any() => return Err(err),
+                Err(err) => {
+                    let node_url = self.nodes[idx].rest_client().node_url();
+                    warn!("node[{node_url}] failed, fail over to next node: {err}");
+                    self.mark_unreachable(idx);
+                    last_err = Some(err);
+                }
+            }
+        }
+        Err(last_err.expect("client pool is never empty"))
+    }
+
+    pub async fn get_index(&self) -> AppResult<IndexData> {
+        self.failover(|node| async move { node.get_index().await })
+            .await
+    }
+
+    pub async fn view_fn_with_err<T: DeserializeOwned + Debug>(
+        &self,
+        args: ViewFnArgs,
+        code: &'static DynErrCode,
+        ext_msg: Option<String>,
+    ) -> AppResult<Response<T>> {
+        self.failover(|node| {
+            let (args, ext_msg) = (args.clone(), ext_msg.clone());
+            async move { node.view_fn_with_err(args, code, ext_msg).await }
+        })
+        .await
+    }
+
+    pub async fn view_fn<T: DeserializeOwned + Debug>(
+        &self,
+        args: ViewFnArgs,
+        code: &'static DynErrCode,
+        ext_msg: Option<String>,
+    ) -> AppResult<T> {
+        let resp = self.view_fn_with_err(args, code, ext_msg).await?;
+        let (_, inner): (u8, T) = resp.into_inner();
+        Ok(inner)
+    }
+
+    /// Sign on the best node, then submit the signed transaction with fail over.
+    /// With a sequence number manager the best node handles the whole submission.
+    pub async fn submit_entry_fn(
+        &self,
+        args: EntryFnArgs<'_>,
+    ) -> AppResult<Response<PendingTransaction>> {
+        let best = self.best().await;
+        let args = best.priced(best.sponsored(args)).await?;
+        best.check_fee(&args, None).await?;
+        if let Some(seq_nums) = best.seq_num_manager() {
+            return seq_nums.submit(&best, args).await;
+        }
+
+        let fn_name = args.fn_name.clone();
+        let signed_txn = best.rest_client().sign_entry_fun(args).await?;
+        let (signed_txn, fn_name) = (&signed_txn, &fn_name);
+        self.failover(|node| async move {
+            let res = node.rest_client().submit_txn(signed_txn).await;
+            res.map_err(map_err!(
+                &EdsErr::SubmitTxnErr,
+                format!("function[{fn_name}]")
+            ))
+        })
+        .await
+    }
+
+    pub async fn entry_fn_wait_txn(
+        &self,
+        args: EntryFnArgs<'_>,
+        gas_used: Option<u64>,
+    ) -> AppResult<Response<Transaction>> {
+        let fn_name = args.fn_name.clone();
+        let fn_args = args.with_overrides(super::gas_used_overrides(&fn_name, gas_used));
+        let pending_tx = self.submit_entry_fn(fn_args).await?.into_inner();
+        info!("entry_fn[{fn_name}] pending_tx_hash: {}", pending_tx.hash);
+        self.wait_for_txn(&pending_tx).await
+    }
+
+    pub async fn wait_for_txn(
+        &self,
+        pending_tx: &PendingTransaction,
+    ) -> AppResult<Response<Transaction>> {
+        self.wait_for_txn_with(pending_tx, WaitConfig::default())
+            .await
+    }
+
+    /// A failed transaction is not failed over, every node would report the same vm status
+    pub async fn wait_for_txn_with(
+        &self,
+        pending_tx: &PendingTransaction,
+        config: WaitConfig,
+    ) -> AppResult<Response<Transaction>> {
+        let txn = self
+            .failover(|node| async move { node.wait_for_committed_txn(pending_tx, config).await })
+            .await?;
+        check_txn_status(txn.inner(), &self.error_maps)?;
+        Ok(txn)
+    }
+
+    /// Node indexes by preference, re-checking health when the last check is stale
+    async fn ranked(&self) -> Vec<usize> {
+        if self.is_stale() {
+            let _refresh = self.refresh.lock().await;
+            // checked by another caller while waiting for the lock
+            if self.is_stale() {
+                self.health_check().await;
+            }
+        }
+
+        let health = self.health.read().expect("pool health lock");
+        health.ranked(self.max_lag)
+    }
+
+    fn is_stale(&self) -> bool {
+        let health = self.health.read().expect("pool health lock");
+        health
+            .checked_at
+            .is_none_or(|at| at.elapsed() >= self.check_interval)
+    }
+
+    fn mark_unreachable(&self, idx: usize) {
+        let mut health = self.health.write().expect("pool health lock");
+        health.versions[idx] = None;
+    }
+}
+
+struct PoolHealth {
+    /// Ledger version per node, None when the node is unreachable
+    versions: Vec<Option<u64>>,
+    checked_at: Option<Instant>,
+    /// Chain id of the first reachable node, unless set by `connect`
+    chain_id: Option<u8>,
+}
+
+impl PoolHealth {
+    /// Nodes within `max_lag` of the most up-to-date one first,
+    /// then lagging nodes, then unreachable ones, each by ledger version desc
+    fn ranked(&self, max_lag: u64) -> Vec<usize> {
+        let latest = self.versions.iter().flatten().max().copied().unwrap_or(0);
+        let mut ranked: Vec<usize> = (0..self.versions.len()).collect();
+        ranked.sort_by_key(|idx| match self.versions[*idx] {
+            Some(version) if latest - version <= max_lag => (0, Reverse(version)),
+            Some(version) => (1, Reverse(version)),
+            None => (2, Reverse(0)),
+        });
+        ranked
+    }
+}
+
+#[async_trait::async_trait]
+impl AcctClientTrait for ClientPool {
+    async fn recover_account(
+        &self,
+        private_key: &str,
+        with_sequence_number: bool,
+    ) -> AppResult<LocalAccount> {
+        let mut acct = private_key.recover_account()?;
+        if with_sequence_number {
+            self.set_latest_sequence_number(&mut acct).await?;
+        }
+        Ok(acct)
+    }
+
+    async fn get_sequence_number(&self, account: &AccountAddress) -> AppResult<u64> {
+        self.failover(|node| async move { node.get_sequence_number(account).await })
+            .await
+    }
+
+    async fn set_latest_sequence_number(&self, account: &mut LocalAccount) -> AppResult<()> {
+        let seq_num = self.get_sequence_number(&account.address()).await?;
+        account.set_sequence_number(seq_num);
+        Ok(())
+    }
+
+    async fn balance_of(&self, owner: Owner) -> AppResult<u128> {
+        self.failover(|node| async move { node.balance_of(owner).await })
+            .await
+    }
+
+    async fn token_balance_of(&self, owner: Owner, token: Token) -> AppResult<u128> {
+        self.failover(|node| async move { node.token_balance_of(owner, token).await })
+            .await
+    }
+
+    async fn faucet(
+        &self,
+        signer: &LocalAccount,
+        receiver: AccountAddress,
+        overrides: Option<Overrides>,
+    ) -> AppResult<Response<PendingTransaction>> {
+        let fn_args = faucet_args(signer, receiver, overrides)?;
+        self.submit_entry_fn(fn_args).await
+    }
+
+    async fn faucet_wait_txn(
+        &self,
+        signer: &LocalAccount,
+        receiver: AccountAddress,
+        overrides: Option<Overrides>,
+    ) -> AppResult<Response<Transaction>> {
+        let res = self.faucet(signer, receiver, overrides).await?;
+        self.wait_for_txn(res.inner()).await
+    }
+
+    async fn simulate_transfer(
+        &self,
+        from: &LocalAccount,
+        to: AccountAddress,
+        amount: u128,
+        overrides: Option<Overrides>,
+    ) -> AppResult<Response<Vec<UserTransaction>>> {
+        self.failover(|node| {
+            let overrides = overrides.clone();
+            async move { node.simulate_transfer(from, to, amount, overrides).await }
+        })
+        .await
+    }
+
+    async fn transfer(
+        &self,
+        from: &LocalAccount,
+        to: AccountAddress,
+        amount: u128,
+        overrides: Option<Overrides>,
+    ) -> AppResult<Response<PendingTransaction>> {
+        let fn_args = transfer_args(from, to, amount, overrides)?;
+        self.submit_entry_fn(fn_args).await
+    }
+
+    async fn transfer_wait_txn(
+        &self,
+        from: &LocalAccount,
+        to: AccountAddress,
+        amount: u128,
+        overrides: Option<Overrides>,
+    ) -> AppResult<Response<Transaction>> {
+        let res = self.transfer(from, to, amount, overrides).await?;
+        self.wait_for_txn(res.inner()).await
+    }
+
+    async fn simulate_transfer_token(
+        &self,
+        from: &LocalAccount,
+        to: AccountAddress,
+        token: Token,
+        amount: u128,
+        overrides: Option<Overrides>,
+    ) -> AppResult<Response<Vec<UserTransaction>>> {
+        self.failover(|node| {
+            let overrides = overrides.clone();
+            async move {
+                node.simulate_transfer_token(from, to, token, amount, overrides)
+                    .await
+            }
+        })
+        .await
+    }
+
+    async fn transfer_token(
+        &self,
+        from: &LocalAccount,
+        to: AccountAddress,
+        token: Token,
+        amount: u128,
+        overrides: Option<Overrides>,
+    ) -> AppResult<Response<PendingTransaction>> {
+        let fn_args = transfer_token_args(from, to, token, amount, overrides)?;
+        self.submit_entry_fn(fn_args).await
+    }
+
+    async fn transfer_token_wait_txn(
+        &self,
+        from: &LocalAccount,
+        to: AccountAddress,
+        token: Token,
+        amount: u128,
+        overrides: Option<Overrides>,
+    ) -> AppResult<Response<Transaction>> {
+        let res = self
+            .transfer_token(from, to, token, amount, overrides)
+            .await?;
+        self.wait_for_txn(res.inner()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_node::{
+        Endpoint, MockLedger, MockNode, MockResponse, TxnOutcome, test_account,
+    };
+
+    fn health(versions: Vec<Option<u64>>) -> PoolHealth {
+        PoolHealth {
+            versions,
+            checked_at: None,
+            chain_id: None,
+        }
+    }
+
+    #[test]
+    fn ranked_by_ledger_version() {
+        let health = health(vec![Some(90), Some(100), Some(95)]);
+        assert_eq!(health.ranked(1_000), vec![1, 2, 0]);
+    }
+
+    #[test]
+    fn lagging_and_unreachable_nodes_last() {
+        let health = health(vec![None, Some(10), Some(5_000), Some(4_990)]);
+        assert_eq!(health.ranked(100), vec![2, 3, 1, 0]);
+    }
+
+    #[test]
+    fn all_unreachable_keeps_order() {
+        let health = health(vec![None, None, None]);
+        assert_eq!(health.ranked(100), vec![0, 1, 2]);
+    }
+
+    #[tokio::test]
+    async fn fails_over_on_node_failures_only() {
+        let (first, second) = (MockNode::start().await, MockNode::start().await);
+        for node in [&first, &second] {
+            node.set_view("endless_coin::balance", MockResponse::view(&7u128));
+        }
+        let pool = ClientPool::connect(vec![first.client(), second.client()]);
+        let pool = pool.await.unwrap();
+        let owner = Owner::new(&AccountAddress::ONE);
+
+        // a view abort would fail on every node alike
+        first.enqueue(Endpoint::View, MockResponse::error(400, "move abort"));
+        assert!(pool.balance_of(owner).await.is_err());
+        assert!(second.requests_to(Endpoint::View).is_empty());
+        assert_eq!(
+            pool.best().await.rest_client().node_url(),
+            first.client().rest_client().node_url()
+        );
+
+        first.enqueue(Endpoint::View, MockResponse::error(503, "overloaded"));
+        assert_eq!(pool.balance_of(owner).await.unwrap(), 7);
+        assert_eq!(second.requests_to(Endpoint::View).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn fails_over_on_any_failed_call() {
+        let (first, second) = (MockNode::start().await, MockNode::start().await);
+        for node in [&first, &second] {
+            node.set_view("endless_coin::balance", MockResponse::view(&7u128));
+        }
+        let pool = ClientPool::new(vec![first.client(), second.client()]).unwrap();
+        let owner = Owner::new(&AccountAddress::ONE);
+
+        // the view fails on the node, the call after it succeeds
+        first.enqueue(Endpoint::View, MockResponse::error(503, "overloaded"));
+        let balance = pool.failover(|node| async move {
+            let balance = node.balance_of(owner).await;
+            node.get_index().await?;
+            balance
+        });
+        assert_eq!(balance.await.unwrap(), 7);
+        assert_eq!(second.requests_to(Endpoint::View).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn wait_names_aborts_with_pool_error_maps() {
+        let node = MockNode::start().await;
+        node.set_txn_outcome(TxnOutcome {
+            success: false,
+            vm_status: "Move abort in 0x1::locking_coin_ex: 0x30002".to_string(),
+            ..TxnOutcome::default()
+        });
+        let user = r#"{ "0x1::locking_coin_ex": { "0x2": { "name": "ENOT_UNLOCKED" } } }"#;
+        let maps = ErrorMaps::framework().clone().with_json(user).unwrap();
+        let pool = ClientPool::new(vec![node.client()]).unwrap();
+        let pool = pool.with_error_maps(maps);
+
+        let from = test_account(0x11);
+        let args = transfer_args(&from, AccountAddress::ONE, 5, None).unwrap();
+        let pending = pool.submit_entry_fn(args).await.unwrap().into_inner();
+        let err = pool.wait_for_txn(&pending).await.unwrap_err();
+        assert!(format!("{err:?}").contains("ENOT_UNLOCKED"), "{err:?}");
+    }
+
+    #[tokio::test]
+    async fn connect_refuses_nodes_of_other_chains() {
+        let (first, second) = (MockNode::start().await, MockNode::start().await);
+        second.set_ledger(MockLedger {
+            chain_id: 5,
+            ..second.ledger()
+        });
+        let pool = ClientPool::connect(vec![first.client(), second.client()]);
+        assert!(pool.await.is_err());
+    }
+}
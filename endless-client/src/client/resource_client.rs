@@ -0,0 +1,293 @@
+use crate::client::EnhancedClient;
+use crate::error::EdsErr;
+use crate::sdk_ext::retry::api_error_code;
+use base_infra::result::AppResult;
+use base_infra::{app_err, map_err};
+use endless_sdk::move_types::account_address::AccountAddress;
+use endless_sdk::move_types::language_storage::StructTag;
+use endless_sdk::rest_client::error::RestError;
+use endless_sdk::rest_client::{EndlessResult, Resource};
+use serde::de::DeserializeOwned;
+use std::collections::BTreeMap;
+
+/// How a resource is read from the node
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ResourceEncoding {
+    /// Field order of the move struct, decodes into any type of the same layout
+    #[default]
+    Bcs,
+    /// Field names of the move struct, u64 and larger integers are json strings
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ResourceOpts {
+    pub encoding: ResourceEncoding,
+    /// Read at this ledger version instead of the latest
+    pub version: Option<u64>,
+}
+
+impl ResourceOpts {
+    pub fn json(self) -> Self {
+        Self {
+            encoding: ResourceEncoding::Json,
+            ..self
+        }
+    }
+
+    pub fn at_version(self, version: u64) -> Self {
+        Self {
+            version: Some(version),
+            ..self
+        }
+    }
+}
+
+#[async_trait::async_trait]
+pub trait ResourceClientTrait {
+    /// Resource `resource_type` (`0x1::module::Struct`) of `address`, bcs decoded at latest
+    async fn get_account_resource<T: DeserializeOwned + Send>(
+        &self,
+        address: AccountAddress,
+        resource_type: &str,
+    ) -> AppResult<T>;
+
+    /// `EdsErr::ResourceNotFound` when the account or the resource does not exist
+    async fn get_account_resource_with<T: DeserializeOwned + Send>(
+        &self,
+        address: AccountAddress,
+        resource_type: &str,
+        opts: ResourceOpts,
+    ) -> AppResult<T>;
+
+    /// All resources of `address` as json
+    async fn get_account_resources(
+        &self,
+        address: AccountAddress,
+        version: Option<u64>,
+    ) -> AppResult<Vec<Resource>>;
+
+    /// All resources of `address` as bcs bytes by type
+    async fn get_account_resources_bcs(
+        &self,
+        address: AccountAddress,
+        version: Option<u64>,
+    ) -> AppResult<BTreeMap<StructTag, Vec<u8>>>;
+}
+
+#[async_trait::async_trait]
+impl ResourceClientTrait for EnhancedClient {
+    async fn get_account_resource<T: DeserializeOwned + Send>(
+        &self,
+        address: AccountAddress,
+        resource_type: &str,
+    ) -> AppResult<T> {
+        let opts = ResourceOpts::default();
+        self.get_account_resource_with(address, resource_type, opts)
+            .await
+    }
+
+    async fn get_account_resource_with<T: DeserializeOwned + Send>(
+        &self,
+        address: AccountAddress,
+        resource_type: &str,
+        opts: ResourceOpts,
+    ) -> AppResult<T> {
+        let (client, msg) = (&self.client, format!("{address} {resource_type}"));
+        match opts.encoding {
+            ResourceEncoding::Bcs => {
+                let res = self
+                    .retry
+                    .run("get_account_resource_bcs", || async move {
+                        match opts.version {
+                            Some(version) => {
+                                client
+                                    .get_account_resource_at_version_bcs(
+                                        address,
+                                        resource_type,
+                                        version,
+                                    )
+                                    .await
+                            }
+                            None => {
+                                client
+                                    .get_account_resource_bcs(address, resource_type)
+                                    .await
+                            }
+                        }
+                    })
+                    .await;
+                Ok(not_found_as_none(res, &msg)?
+                    .ok_or_else(|| app_err!(&EdsErr::ResourceNotFound, msg))?
+                    .into_inner())
+            }
+            ResourceEncoding::Json => {
+                let res = self
+                    .retry
+                    .run("get_account_resource", || async move {
+                        match opts.version {
+                            Some(version) => {
+                                client
+                                    .get_account_resource_at_version(
+                                        address,
+                                        resource_type,
+                                        version,
+                                    )
+                                    .await
+                            }
+                            None => client.get_account_resource(address, resource_type).await,
+                        }
+                    })
+                    .await;
+                let resource = not_found_as_none(res, &msg)?
+                    .and_then(|resp| resp.into_inner())
+                    .ok_or_else(|| app_err!(&EdsErr::ResourceNotFound, msg.clone()))?;
+                serde_json::from_value(resource.data)
+                    .map_err(map_err!(&EdsErr::DecodeResource, msg))
+            }
+        }
+    }
+
+    async fn get_account_resources(
+        &self,
+        address: AccountAddress,
+        version: Option<u64>,
+    ) -> AppResult<Vec<Resource>> {
+        let (client, msg) = (&self.client, address.to_string());
+        let res = self
+            .retry
+            .run("get_account_resources", || async move {
+                match version {
+                    Some(version) => {
+                        client
+                            .get_account_resources_at_version(address, version)
+                            .await
+                    }
+                    None => client.get_account_resources(address).await,
+                }
+            })
+            .await;
+        let resources = not_found_as_none(res, &msg)?
+            .ok_or_else(|| app_err!(&EdsErr::ResourceNotFound, msg))?;
+        Ok(resources.into_inner())
+    }
+
+    async fn get_account_resources_bcs(
+        &self,
+        address: AccountAddress,
+        version: Option<u64>,
+    ) -> AppResult<BTreeMap<StructTag, Vec<u8>>> {
+        let (client, msg) = (&self.client, address.to_string());
+        let res = self
+            .retry
+            .run("get_account_resources_bcs", || async move {
+                match version {
+                    Some(version) => {
+                        client
+                            .get_account_resources_at_version_bcs(address, version)
+                            .await
+                    }
+                    None => client.get_account_resources_bcs(address).await,
+                }
+            })
+            .await;
+        let resources = not_found_as_none(res, &msg)?
+            .ok_or_else(|| app_err!(&EdsErr::ResourceNotFound, msg))?;
+        Ok(resources.into_inner())
+    }
+}
+
+/// A missing account or resource is None, a resource of another layout a decode error,
+/// any other failure, a missing or pruned version too, an error
+fn not_found_as_none<T>(res: EndlessResult<T>, msg: &str) -> AppResult<Option<T>> {
+    match res {
+        Ok(resp) => Ok(Some(resp)),
+        Err(err) if is_not_found(&err) => Ok(None),
+        Err(RestError::Bcs(err)) => {
+            Err(err).map_err(map_err!(&EdsErr::DecodeResource, msg.to_string()))
+        }
+        Err(err) => Err(err).map_err(map_err!(&EdsErr::GetAccountResource, msg.to_string())),
+    }
+}
+
+fn is_not_found(err: &RestError) -> bool {
+    matches!(
+        api_error_code(err).as_deref(),
+        Some("resource_not_found" | "account_not_found")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::is_err_code;
+    use crate::mock_node::{Endpoint, MockNode, MockResponse};
+    use serde::Deserialize;
+
+    const METADATA: &str = "0x1::fungible_asset::Metadata";
+
+    #[derive(Debug, Deserialize)]
+    struct Metadata {
+        symbol: String,
+        decimals: u8,
+    }
+
+    fn assert_err<T: std::fmt::Debug>(res: AppResult<T>, code: &EdsErr) {
+        let err = res.unwrap_err();
+        assert!(is_err_code(&err, code), "got {err:?}");
+    }
+
+    /// Fungible asset at `[7; 32]` with its metadata
+    async fn serve_metadata() -> (MockNode, AccountAddress) {
+        let node = MockNode::start().await;
+        let asset = AccountAddress::new([7; 32]);
+        let metadata = ("EDS", 8u8);
+        let metadata = MockResponse::bcs(bcs::to_bytes(&metadata).unwrap());
+        node.set_resource(asset, METADATA, metadata);
+        (node, asset)
+    }
+
+    #[tokio::test]
+    async fn found_resource_and_other_layout() {
+        let (node, asset) = serve_metadata().await;
+        let client = node.client();
+
+        let found: Metadata = client.get_account_resource(asset, METADATA).await.unwrap();
+        assert_eq!((found.symbol.as_str(), found.decimals), ("EDS", 8));
+
+        let res = client.get_account_resource::<(u64, u64)>(asset, METADATA);
+        assert_err(res.await, &EdsErr::DecodeResource);
+    }
+
+    #[tokio::test]
+    async fn missing_resource_and_account() {
+        let (node, asset) = serve_metadata().await;
+        let client = node.client();
+
+        let res = client.get_account_resource::<Metadata>(asset, "0x1::object::ObjectCore");
+        assert_err(res.await, &EdsErr::ResourceNotFound);
+
+        let missing = MockResponse::api_error(404, "account_not_found", "Account not found");
+        node.enqueue(Endpoint::Resource, missing);
+        let res = client.get_account_resource::<Metadata>(AccountAddress::ONE, METADATA);
+        assert_err(res.await, &EdsErr::ResourceNotFound);
+    }
+
+    #[tokio::test]
+    async fn missing_or_pruned_version_is_an_error() {
+        let (node, asset) = serve_metadata().await;
+        let client = node.client();
+        let opts = ResourceOpts::default().at_version(5000);
+
+        let ahead = MockResponse::api_error(404, "version_not_found", "Version not found");
+        node.enqueue(Endpoint::Resource, ahead);
+        let res = client.get_account_resource_with::<Metadata>(asset, METADATA, opts);
+        assert_err(res.await, &EdsErr::GetAccountResource);
+        let query = node.requests_to(Endpoint::Resource)[0].query.clone();
+        assert_eq!(query.as_deref(), Some("ledger_version=5000"));
+
+        node.enqueue(Endpoint::Resource, MockResponse::error(410, "pruned"));
+        let res = client.get_account_resource_with::<Metadata>(asset, METADATA, opts);
+        assert_err(res.await, &EdsErr::GetAccountResource);
+    }
+}
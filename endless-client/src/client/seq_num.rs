@@ -0,0 +1,319 @@
+use crate::client::EnhancedClient;
+use crate::client::account_client::AcctClientTrait;
+use crate::error::EdsErr;
+use crate::sdk_ext::types::EntryFnArgs;
+use base_infra::map_err;
+use base_infra::result::AppResult;
+use endless_sdk::move_types::account_address::AccountAddress;
+use endless_sdk::rest_client::error::RestError;
+use endless_sdk::rest_client::{PendingTransaction, Response};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+/// How many times a submission is re-signed after a sequence number rejection
+const MAX_RESYNC: usize = 3;
+
+/// Vm status codes the node rejects stale or out-of-order sequence numbers with
+const SEQUENCE_NUMBER_TOO_OLD: u64 = 3;
+const SEQUENCE_NUMBER_TOO_NEW: u64 = 4;
+
+/// Local sequence number allocator, shared by all tasks submitting for the same accounts.
+#[derive(Clone, Default)]
+pub struct SeqNumManager {
+    accounts: Arc<Mutex<HashMap<AccountAddress, AcctSeqNums>>>,
+}
+
+impl SeqNumManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allocate the next sequence number of `account`, syncing from chain on first use
+    pub async fn allocate(
+        &self,
+        client: &EnhancedClient,
+        account: AccountAddress,
+    ) -> AppResult<u64> {
+        if let Some(seq_nums) = self.accounts.lock().await.get_mut(&account) {
+            return Ok(seq_nums.allocate());
+        }
+
+        // fetched without the lock, so a slow node does not hold up other accounts
+        let on_chain = client.get_sequence_number(&account).await?;
+        let mut accounts = self.accounts.lock().await;
+        // another task may have synced the account meanwhile, its numbers are in use
+        let seq_nums = accounts
+            .entry(account)
+            .or_insert_with(|| AcctSeqNums::new(on_chain));
+        Ok(seq_nums.allocate())
+    }
+
+    /// Sequence number the next `allocate` of `account` hands out, without reserving it
+    pub async fn peek(&self, client: &EnhancedClient, account: AccountAddress) -> AppResult<u64> {
+        if let Some(seq_nums) = self.accounts.lock().await.get(&account) {
+            return Ok(seq_nums.peek());
+        }
+
+        let on_chain = client.get_sequence_number(&account).await?;
+        let mut accounts = self.accounts.lock().await;
+        let seq_nums = accounts
+            .entry(account)
+            .or_insert_with(|| AcctSeqNums::new(on_chain));
+        Ok(seq_nums.peek())
+    }
+
+    /// The transaction of `seq_num` is committed, it is no longer in flight
+    pub async fn confirm(&self, account: AccountAddress, seq_num: u64) {
+        if let Some(seq_nums) = self.accounts.lock().await.get_mut(&account) {
+            seq_nums.confirm(seq_num);
+        }
+    }
+
+    /// The transaction of `seq_num` never reached the chain, hand the number out again
+    pub async fn release(&self, account: AccountAddress, seq_num: u64) {
+        if let Some(seq_nums) = self.accounts.lock().await.get_mut(&account) {
+            seq_nums.release(seq_num);
+        }
+    }
+
+    /// Resync `account` from chain, keeping in-flight numbers the chain has not reached yet
+    pub async fn resync(&self, client: &EnhancedClient, account: AccountAddress) -> AppResult<u64> {
+        let on_chain = client.get_sequence_number(&account).await?;
+        let mut accounts = self.accounts.lock().await;
+        let seq_nums = accounts
+            .entry(account)
+            .or_insert_with(|| AcctSeqNums::new(on_chain));
+        seq_nums.resync(on_chain);
+        info!("resync sequence number of {account} from chain: {on_chain}");
+        Ok(on_chain)
+    }
+
+    /// Sequence numbers submitted for `account` but not yet confirmed or released
+    pub async fn in_flight(&self, account: &AccountAddress) -> Vec<u64> {
+        match self.accounts.lock().await.get(account) {
+            Some(seq_nums) => seq_nums.in_flight.iter().copied().collect(),
+            None => vec![],
+        }
+    }
+
+    /// Sign `args` with an allocated sequence number and submit it,
+    /// resyncing and re-signing when the node rejects the sequence number
+    pub async fn submit(
+        &self,
+        client: &EnhancedClient,
+        args: EntryFnArgs<'_>,
+    ) -> AppResult<Response<PendingTransaction>> {
+        let (sender, fn_name) = (args.signer.address(), args.fn_name.clone());
+        let rest_client = client.rest_client();
+
+        let mut resynced = 0;
+        loop {
+            let seq_num = self.allocate(client, sender).await?;
+            let fn_args = args.clone().with_sequence_number(Some(seq_num));
+            let signed_txn = match rest_client.sign_entry_fun(fn_args).await {
+                Ok(signed_txn) => signed_txn,
+                Err(err) => {
+                    self.release(sender, seq_num).await;
+                    return Err(err);
+                }
+            };
+
+            match rest_client.submit_txn(&signed_txn).await {
+                Ok(res) => return Ok(res),
+                Err(err) if is_seq_num_rejection(&err) && resynced < MAX_RESYNC => {
+                    warn!("function[{fn_name}] sequence number {seq_num} rejected: {err}");
+                    self.release(sender, seq_num).await;
+                    self.resync(client, sender).await?;
+                    resynced += 1;
+                }
+                Err(err) => {
+                    // without a verdict the transaction may still reach the chain,
+                    // its number stays in flight until it is waited for or resynced
+                    if is_definitive_rejection(&err) {
+                        self.release(sender, seq_num).await;
+                    }
+                    let msg = format!("function[{fn_name}]");
+                    return Err(err).map_err(map_err!(&EdsErr::SubmitTxnErr, msg));
+                }
+            }
+        }
+    }
+}
+
+/// Whether the node rejected the transaction for its sequence number
+pub(crate) fn is_seq_num_rejection(err: &RestError) -> bool {
+    let RestError::Api(resp) = err else {
+        return false;
+    };
+
+    matches!(
+        resp.error.vm_error_code,
+        Some(SEQUENCE_NUMBER_TOO_OLD | SEQUENCE_NUMBER_TOO_NEW)
+    ) || resp.error.message.contains("SEQUENCE_NUMBER_TOO_")
+}
+
+/// Whether the node refused the transaction in validation, so it never enters the mempool:
+/// a client error carrying a vm status. Timeouts, transport and server errors are no verdict
+pub(crate) fn is_definitive_rejection(err: &RestError) -> bool {
+    let RestError::Api(resp) = err else {
+        return false;
+    };
+
+    resp.status_code.is_client_error() && resp.error.vm_error_code.is_some()
+}
+
+#[derive(Debug)]
+struct AcctSeqNums {
+    next: u64,
+    in_flight: BTreeSet<u64>,
+    released: BTreeSet<u64>,
+}
+
+impl AcctSeqNums {
+    fn new(on_chain: u64) -> Self {
+        Self {
+            next: on_chain,
+            in_flight: BTreeSet::new(),
+            released: BTreeSet::new(),
+        }
+    }
+
+    /// Refill the lowest gap first, so later transactions are not stuck behind it
+    fn allocate(&mut self) -> u64 {
+        let seq_num = self.released.pop_first().unwrap_or_else(|| {
+            self.next += 1;
+            self.next - 1
+        });
+        self.in_flight.insert(seq_num);
+        seq_num
+    }
+
+    fn peek(&self) -> u64 {
+        self.released.first().copied().unwrap_or(self.next)
+    }
+
+    fn confirm(&mut self, seq_num: u64) {
+        self.in_flight.remove(&seq_num);
+    }
+
+    fn release(&mut self, seq_num: u64) {
+        if self.in_flight.remove(&seq_num) {
+            self.released.insert(seq_num);
+        }
+    }
+
+    fn resync(&mut self, on_chain: u64) {
+        self.in_flight.retain(|n| *n >= on_chain);
+        self.released.retain(|n| *n >= on_chain);
+        self.next = self.next.max(on_chain);
+        // numbers between chain and local that are neither in flight nor released are gaps
+        for seq_num in on_chain..self.next {
+            if !self.in_flight.contains(&seq_num) {
+                self.released.insert(seq_num);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_node::{Endpoint, MockNode, MockResponse, test_account};
+
+    #[test]
+    fn allocate_refills_released_gaps_first() {
+        let mut seq_nums = AcctSeqNums::new(10);
+        assert_eq!(seq_nums.allocate(), 10);
+        assert_eq!(seq_nums.allocate(), 11);
+        assert_eq!(seq_nums.allocate(), 12);
+
+        seq_nums.release(11);
+        assert_eq!(seq_nums.peek(), 11);
+        assert_eq!(seq_nums.allocate(), 11);
+        assert_eq!(seq_nums.peek(), 13);
+        assert_eq!(seq_nums.allocate(), 13);
+    }
+
+    #[test]
+    fn release_ignores_confirmed() {
+        let mut seq_nums = AcctSeqNums::new(0);
+        let seq_num = seq_nums.allocate();
+        seq_nums.confirm(seq_num);
+        seq_nums.release(seq_num);
+        assert_eq!(seq_nums.allocate(), 1);
+    }
+
+    #[test]
+    fn resync_drops_committed_and_fills_gaps() {
+        let mut seq_nums = AcctSeqNums::new(5);
+        for _ in 0..4 {
+            seq_nums.allocate();
+        }
+        // 5 and 6 committed, 7 confirmed but dropped, 8 still in flight
+        seq_nums.confirm(7);
+        seq_nums.resync(7);
+
+        assert_eq!(seq_nums.in_flight, BTreeSet::from([8]));
+        assert_eq!(seq_nums.allocate(), 7);
+        assert_eq!(seq_nums.allocate(), 9);
+    }
+
+    #[test]
+    fn resync_jumps_ahead_of_chain() {
+        let mut seq_nums = AcctSeqNums::new(3);
+        seq_nums.allocate();
+        seq_nums.resync(20);
+
+        assert!(seq_nums.in_flight.is_empty());
+        assert_eq!(seq_nums.allocate(), 20);
+    }
+
+    #[tokio::test]
+    async fn lost_submit_response_keeps_number_in_flight() {
+        let node = MockNode::start().await;
+        node.set_view("account::get_sequence_number", MockResponse::view(&3u64));
+        node.drop_next(Endpoint::Submit);
+        let seq_nums = SeqNumManager::new();
+        let client = node.client().with_seq_num_manager(seq_nums.clone());
+        let from = test_account(0x11);
+
+        // the node accepted the transaction, only its answer was lost
+        let lost = client.transfer(&from, AccountAddress::ONE, 5, None).await;
+        assert!(lost.is_err());
+        assert_eq!(seq_nums.in_flight(&from.address()).await, vec![3]);
+
+        client
+            .transfer(&from, AccountAddress::ONE, 5, None)
+            .await
+            .unwrap();
+        let submitted = node.submitted_txns();
+        let submitted: Vec<u64> = submitted.iter().map(|txn| txn.sequence_number()).collect();
+        assert_eq!(submitted, vec![3, 4]);
+    }
+
+    #[tokio::test]
+    async fn simulates_after_transactions_in_flight() {
+        let node = MockNode::start().await;
+        node.set_pending_polls(usize::MAX);
+        let from = test_account(0x11);
+        node.set_view("account::get_sequence_number", MockResponse::view(&7u64));
+        let client = node.client().with_seq_num_manager(SeqNumManager::new());
+
+        client
+            .transfer(&from, AccountAddress::ONE, 5, None)
+            .await
+            .unwrap();
+        assert_eq!(node.submitted_txns()[0].sequence_number(), 7);
+
+        // the local account still counts from 0, the manager has 7 in flight
+        client
+            .simulate_transfer(&from, AccountAddress::ONE, 5, None)
+            .await
+            .unwrap();
+        let simulated = node.requests_to(Endpoint::Simulate)[0].signed_txn.clone();
+        assert_eq!(simulated.unwrap().sequence_number(), 8);
+        assert_eq!(from.sequence_number(), 0);
+    }
+}
@@ -0,0 +1,259 @@
+use crate::client::EnhancedClient;
+use crate::client::account_client::{transfer_args, transfer_token_args};
+use crate::client::dec_account_client::from_chain_amount;
+use crate::client::txn_stream::{FUNGIBLE_STORE, written_stores};
+use crate::client::types::{Token, TxnStatus};
+use crate::error::EdsErr;
+use crate::sdk_ext::events::{FaDeposit, FaWithdraw, TxnEvents};
+use crate::sdk_ext::types::{EntryFnArgs, ViewFnArgs};
+use crate::utils::bcs_ext::BcsExt;
+use base_infra::app_err;
+use base_infra::result::AppResult;
+use bigdecimal::BigDecimal;
+use endless_sdk::helper_client::Overrides;
+use endless_sdk::move_types::account_address::AccountAddress;
+use endless_sdk::rest_client::endless_api_types::{Event, UserTransaction};
+use endless_sdk::types::LocalAccount;
+use std::collections::{BTreeMap, HashMap};
+
+/// Balance change of one fungible asset of one account, negative when it decreases
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BalanceDelta {
+    pub account: AccountAddress,
+    /// Fungible asset metadata address
+    pub asset: AccountAddress,
+    pub amount: i128,
+}
+
+/// Outcome of a simulated transaction, what it would do if signed and submitted
+#[derive(Debug, Clone)]
+pub struct SimulationReport {
+    pub success: bool,
+    pub status: TxnStatus,
+    pub vm_status: String,
+    pub gas_used: u64,
+    pub gas_unit_price: u64,
+    /// gas_used * gas_unit_price in EDS, paid by the fee payer if any, otherwise by the sender
+    pub fee: BigDecimal,
+    pub events: Vec<Event>,
+    /// Withdrawn and deposited amounts by account and asset, the fee not included
+    pub balance_deltas: Vec<BalanceDelta>,
+}
+
+impl SimulationReport {
+    /// Change of `account`'s balance of `asset`, 0 when unchanged
+    pub fn delta_of(&self, account: AccountAddress, asset: AccountAddress) -> i128 {
+        self.balance_deltas
+            .iter()
+            .find(|delta| delta.account == account && delta.asset == asset)
+            .map_or(0, |delta| delta.amount)
+    }
+}
+
+/// Simulations summarized for display before signing
+#[async_trait::async_trait]
+pub trait SimulationClientTrait {
+    /// Simulate `args` like `simulate_entry_fn`, priced like `submit_entry_fn`,
+    /// and report its outcome, fee and balance changes
+    async fn simulation_report(&self, args: EntryFnArgs<'_>) -> AppResult<SimulationReport>;
+
+    async fn simulate_transfer_report(
+        &self,
+        from: &LocalAccount,
+        to: AccountAddress,
+        amount: u128,
+        overrides: Option<Overrides>,
+    ) -> AppResult<SimulationReport>;
+
+    async fn simulate_transfer_token_report(
+        &self,
+        from: &LocalAccount,
+        to: AccountAddress,
+        token: Token,
+        amount: u128,
+        overrides: Option<Overrides>,
+    ) -> AppResult<SimulationReport>;
+}
+
+#[async_trait::async_trait]
+impl SimulationClientTrait for EnhancedClient {
+    async fn simulation_report(&self, args: EntryFnArgs<'_>) -> AppResult<SimulationReport> {
+        let fn_name = args.fn_name.clone();
+        let res = self.simulate_entry_fn(self.priced(args).await?).await?;
+        let simulated = res.inner().first().ok_or_else(|| {
+            let msg = format!("function[{fn_name}] empty simulation result");
+            app_err!(&EdsErr::SimulateTxnErr, msg)
+        })?;
+
+        let (gas_used, gas_unit_price) = (
+            simulated.info.gas_used.0,
+            simulated.request.gas_unit_price.0,
+        );
+        let fee = gas_used as u128 * gas_unit_price as u128;
+        Ok(SimulationReport {
+            success: simulated.info.success,
+            status: self.simulated_status(simulated),
+            vm_status: simulated.info.vm_status.clone(),
+            gas_used,
+            gas_unit_price,
+            fee: from_chain_amount(fee, self.eds_decimals().await?)?,
+            events: simulated.events.clone(),
+            balance_deltas: self.balance_deltas(simulated).await?,
+        })
+    }
+
+    async fn simulate_transfer_report(
+        &self,
+        from: &LocalAccount,
+        to: AccountAddress,
+        amount: u128,
+        overrides: Option<Overrides>,
+    ) -> AppResult<SimulationReport> {
+        let fn_args = transfer_args(from, to, amount, overrides)?;
+        self.simulation_report(fn_args).await
+    }
+
+    async fn simulate_transfer_token_report(
+        &self,
+        from: &LocalAccount,
+        to: AccountAddress,
+        token: Token,
+        amount: u128,
+        overrides: Option<Overrides>,
+    ) -> AppResult<SimulationReport> {
+        let fn_args = transfer_token_args(from, to, token, amount, overrides)?;
+        self.simulation_report(fn_args).await
+    }
+}
+
+impl EnhancedClient {
+    /// Withdraw and deposit events summed per owner and asset of their stores,
+    /// stores the write set does not describe are looked up on chain
+    async fn balance_deltas(&self, txn: &UserTransaction) -> AppResult<Vec<BalanceDelta>> {
+        let written = written_stores(&txn.info.changes);
+        let mut deltas: BTreeMap<(AccountAddress, AccountAddress), i128> = BTreeMap::new();
+        for (store, amount) in store_moves(txn)? {
+            let known = written.get(&store);
+            let owner = match known.and_then(|s| s.owner) {
+                Some(owner) => owner,
+                None => self.store_view(store, "object", "owner").await?,
+            };
+            let asset = match known.and_then(|s| s.asset) {
+                Some(asset) => asset,
+                None => {
+                    self.store_view(store, "fungible_asset", "store_metadata")
+                        .await?
+                }
+            };
+            *deltas.entry((owner, asset)).or_default() += amount;
+        }
+
+        let deltas = deltas.into_iter().filter(|(_, amount)| *amount != 0);
+        let deltas = deltas.map(|((account, asset), amount)| BalanceDelta {
+            account,
+            asset,
+            amount,
+        });
+        Ok(deltas.collect())
+    }
+
+    async fn store_view(
+        &self,
+        store: AccountAddress,
+        mn: &str,
+        fun: &str,
+    ) -> AppResult<AccountAddress> {
+        let (args, t_args) = (vec![store.to_bytes()?], vec![FUNGIBLE_STORE]);
+        let args = ViewFnArgs::new(AccountAddress::ONE, mn, fun, args, t_args)?;
+        let ext_msg = Some(format!("{mn}::{fun} of store {store}"));
+        self.view_fn(args, &EdsErr::SimStoreView, ext_msg).await
+    }
+}
+
+/// Amount moved in and out of each store by fungible asset events
+fn store_moves(txn: &UserTransaction) -> AppResult<HashMap<AccountAddress, i128>> {
+    let mut moves: HashMap<AccountAddress, i128> = HashMap::new();
+    for withdraw in txn.events_of::<FaWithdraw>()? {
+        *moves.entry(withdraw.store).or_default() -= withdraw.amount as i128;
+    }
+    for deposit in txn.events_of::<FaDeposit>()? {
+        *moves.entry(deposit.store).or_default() += deposit.amount as i128;
+    }
+    Ok(moves)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::fee_policy::FeePolicy;
+    use crate::mock_node::{
+        Endpoint, MockNode, MockResponse, TxnOutcome, fa_event, store_writes, test_account,
+    };
+    use crate::sdk_ext::types::GasPriceLevel;
+
+    #[tokio::test]
+    async fn simulation_report_with_balance_deltas() {
+        let node = MockNode::start().await;
+        let (from, to) = (test_account(0x11), AccountAddress::new([2; 32]));
+        let eds = AccountAddress::new([0xe; 32]);
+        let (from_store, to_store) = (AccountAddress::new([3; 32]), AccountAddress::new([4; 32]));
+
+        node.serve_eds(eds);
+
+        // the object of the recipient's store is not written, its owner is viewed
+        node.set_view("object::owner", MockResponse::view(&to));
+        let mut changes = store_writes(from_store, Some(from.address()), eds);
+        changes.extend(store_writes(to_store, None, eds));
+        node.set_txn_outcome(TxnOutcome {
+            events: vec![
+                fa_event("Withdraw", from_store, 5),
+                fa_event("Deposit", to_store, 5),
+            ],
+            changes,
+            ..TxnOutcome::default()
+        });
+
+        let client = node.client();
+        let report = client.simulate_transfer_report(&from, to, 5, None);
+        let report = report.await.unwrap();
+        assert!(report.success && report.status.is_success());
+        assert_eq!(report.events.len(), 2);
+        assert_eq!(report.delta_of(from.address(), eds), -5);
+        assert_eq!(report.delta_of(to, eds), 5);
+        assert_eq!(report.balance_deltas.len(), 2);
+        let fee = report.gas_used as u128 * report.gas_unit_price as u128;
+        assert_eq!(report.fee, from_chain_amount(fee, 8).unwrap());
+    }
+
+    #[tokio::test]
+    async fn failed_simulation_report_priced_by_policy() {
+        let node = MockNode::start().await;
+        let (from, to) = (test_account(0x11), AccountAddress::new([2; 32]));
+        node.serve_eds(AccountAddress::new([0xe; 32]));
+        node.set_txn_outcome(TxnOutcome {
+            success: false,
+            vm_status: "Move abort in 0x1::fungible_asset: 0x10004".to_string(),
+            gas_used: 7,
+            ..TxnOutcome::default()
+        });
+
+        let policy = FeePolicy::new(GasPriceLevel::Prioritized);
+        let client = node.client().with_fee_policy(policy);
+        let report = client.simulate_transfer_report(&from, to, 5, None);
+        let report = report.await.unwrap();
+        assert!(!report.success);
+        match &report.status {
+            TxnStatus::MoveAbort(abort) => {
+                assert_eq!(abort.reason_name(), Some("EINSUFFICIENT_BALANCE"))
+            }
+            other => panic!("expected a move abort, got {other:?}"),
+        }
+        assert!(report.events.is_empty() && report.balance_deltas.is_empty());
+
+        // simulated at the price the transaction would be submitted with
+        let simulated = node.requests_to(Endpoint::Simulate)[0].signed_txn.clone();
+        assert_eq!(simulated.unwrap().gas_unit_price(), 150);
+        assert_eq!(report.gas_unit_price, 150);
+        assert_eq!(report.fee, from_chain_amount(7 * 150, 8).unwrap());
+    }
+}
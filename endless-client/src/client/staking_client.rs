@@ -0,0 +1,338 @@
+use crate::client::EnhancedClient;
+use crate::client::dec_account_client::{DecAcctClientTrait, from_chain_amount, to_chain_amount};
+use crate::client::fa_client::FaClientTrait;
+use crate::client::resource_client::{ResourceClientTrait, ResourceOpts};
+use crate::error::EdsErr;
+use crate::sdk_ext::events::from_str_or_num;
+use crate::sdk_ext::types::{EntryFnArgs, ViewFnArgs};
+use crate::utils::bcs_ext::BcsExt;
+use base_infra::result::AppResult;
+use bigdecimal::BigDecimal;
+use endless_sdk::helper_client::Overrides;
+use endless_sdk::move_types::account_address::AccountAddress;
+use endless_sdk::rest_client::{PendingTransaction, Response};
+use endless_sdk::types::LocalAccount;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use std::fmt::Debug;
+
+const DELEGATION_MODULE: &str = "delegation_pool";
+const STAKING_CONFIG: &str = "0x1::staking_config::StakingConfig";
+
+/// Commission is in hundredths of a percent
+const COMMISSION_DENOMINATOR: u128 = 10_000;
+
+/// `delegation_pool::get_stake` of a delegator, in whole EDS
+#[derive(Debug, Clone, PartialEq)]
+pub struct DelegatorStake {
+    pub active: BigDecimal,
+    /// Unlocked and withdrawable
+    pub inactive: BigDecimal,
+    /// Unlocking at the end of the current lockup
+    pub pending_inactive: BigDecimal,
+}
+
+/// Reward rate fields of `0x1::staking_config::StakingConfig`, u64 json strings on chain
+#[derive(Deserialize)]
+struct RewardRate {
+    #[serde(deserialize_with = "from_str_or_num")]
+    rewards_rate: u128,
+    #[serde(deserialize_with = "from_str_or_num")]
+    rewards_rate_denominator: u128,
+}
+
+/// Stake of a delegator in a `0x1::delegation_pool`, amounts in whole EDS
+#[async_trait::async_trait]
+pub trait StakingClientTrait {
+    async fn add_stake(
+        &self,
+        delegator: &LocalAccount,
+        pool: AccountAddress,
+        amount: &BigDecimal,
+        overrides: Option<Overrides>,
+    ) -> AppResult<Response<PendingTransaction>>;
+
+    /// Move active stake to pending inactive, withdrawable once the lockup ends
+    async fn unlock_stake(
+        &self,
+        delegator: &LocalAccount,
+        pool: AccountAddress,
+        amount: &BigDecimal,
+        overrides: Option<Overrides>,
+    ) -> AppResult<Response<PendingTransaction>>;
+
+    /// Withdraw inactive stake to the primary store of the delegator
+    async fn withdraw_stake(
+        &self,
+        delegator: &LocalAccount,
+        pool: AccountAddress,
+        amount: &BigDecimal,
+        overrides: Option<Overrides>,
+    ) -> AppResult<Response<PendingTransaction>>;
+
+    /// Move pending inactive stake back to active
+    async fn reactivate_stake(
+        &self,
+        delegator: &LocalAccount,
+        pool: AccountAddress,
+        amount: &BigDecimal,
+        overrides: Option<Overrides>,
+    ) -> AppResult<Response<PendingTransaction>>;
+
+    async fn delegator_stake(
+        &self,
+        pool: AccountAddress,
+        delegator: AccountAddress,
+    ) -> AppResult<DelegatorStake>;
+
+    /// Operator commission of `pool` in hundredths of a percent, 1000 is 10%
+    async fn operator_commission(&self, pool: AccountAddress) -> AppResult<u64>;
+
+    /// Reward of the active stake of `delegator` for one epoch after commission,
+    /// at the current reward rate
+    async fn estimate_epoch_reward(
+        &self,
+        pool: AccountAddress,
+        delegator: AccountAddress,
+    ) -> AppResult<BigDecimal>;
+}
+
+#[async_trait::async_trait]
+impl StakingClientTrait for EnhancedClient {
+    async fn add_stake(
+        &self,
+        delegator: &LocalAccount,
+        pool: AccountAddress,
+        amount: &BigDecimal,
+        overrides: Option<Overrides>,
+    ) -> AppResult<Response<PendingTransaction>> {
+        self.delegation_entry(delegator, pool, "add_stake", amount, overrides)
+            .await
+    }
+
+    async fn unlock_stake(
+        &self,
+        delegator: &LocalAccount,
+        pool: AccountAddress,
+        amount: &BigDecimal,
+        overrides: Option<Overrides>,
+    ) -> AppResult<Response<PendingTransaction>> {
+        self.delegation_entry(delegator, pool, "unlock", amount, overrides)
+            .await
+    }
+
+    async fn withdraw_stake(
+        &self,
+        delegator: &LocalAccount,
+        pool: AccountAddress,
+        amount: &BigDecimal,
+        overrides: Option<Overrides>,
+    ) -> AppResult<Response<PendingTransaction>> {
+        self.delegation_entry(delegator, pool, "withdraw", amount, overrides)
+            .await
+    }
+
+    async fn reactivate_stake(
+        &self,
+        delegator: &LocalAccount,
+        pool: AccountAddress,
+        amount: &BigDecimal,
+        overrides: Option<Overrides>,
+    ) -> AppResult<Response<PendingTransaction>> {
+        self.delegation_entry(delegator, pool, "reactivate_stake", amount, overrides)
+            .await
+    }
+
+    async fn delegator_stake(
+        &self,
+        pool: AccountAddress,
+        delegator: AccountAddress,
+    ) -> AppResult<DelegatorStake> {
+        let (active, inactive, pending_inactive) = self.chain_stake(pool, delegator).await?;
+
+        let decimals = self.eds_decimals().await?;
+        Ok(DelegatorStake {
+            active: from_chain_amount(active, decimals)?,
+            inactive: from_chain_amount(inactive, decimals)?,
+            pending_inactive: from_chain_amount(pending_inactive, decimals)?,
+        })
+    }
+
+    async fn operator_commission(&self, pool: AccountAddress) -> AppResult<u64> {
+        let args = vec![pool.to_bytes()?];
+        self.delegation_view("operator_commission_percentage", args)
+            .await
+    }
+
+    async fn estimate_epoch_reward(
+        &self,
+        pool: AccountAddress,
+        delegator: AccountAddress,
+    ) -> AppResult<BigDecimal> {
+        let (active, _, _) = self.chain_stake(pool, delegator).await?;
+        let commission = self.operator_commission(pool).await?;
+
+        let opts = ResourceOpts::default().json();
+        let rate: RewardRate = self
+            .get_account_resource_with(AccountAddress::ONE, STAKING_CONFIG, opts)
+            .await?;
+
+        let reward = epoch_reward(
+            active,
+            rate.rewards_rate,
+            rate.rewards_rate_denominator,
+            commission,
+        );
+        from_chain_amount(reward, self.eds_decimals().await?)
+    }
+}
+
+impl EnhancedClient {
+    pub(crate) async fn eds_decimals(&self) -> AppResult<u8> {
+        Ok(self.fa_metadata(self.eds_token().await?).await?.decimals)
+    }
+
+    async fn delegation_entry(
+        &self,
+        delegator: &LocalAccount,
+        pool: AccountAddress,
+        fun: &str,
+        amount: &BigDecimal,
+        overrides: Option<Overrides>,
+    ) -> AppResult<Response<PendingTransaction>> {
+        let amount = to_chain_amount(amount, self.eds_decimals().await?)?;
+        let args = vec![pool.to_bytes()?, amount.to_bytes()?];
+        let mn = DELEGATION_MODULE;
+        let fn_args = EntryFnArgs::new(delegator, AccountAddress::ONE, mn, fun, args, vec![])?;
+        self.submit_entry_fn(fn_args.with_overrides(overrides))
+            .await
+    }
+
+    /// `delegation_pool::get_stake`, `(active, inactive, pending_inactive)` u128 amounts
+    async fn chain_stake(
+        &self,
+        pool: AccountAddress,
+        delegator: AccountAddress,
+    ) -> AppResult<(u128, u128, u128)> {
+        let args = vec![pool.to_bytes()?, delegator.to_bytes()?];
+        self.delegation_view("get_stake", args).await
+    }
+
+    async fn delegation_view<T: DeserializeOwned + Debug>(
+        &self,
+        fun: &str,
+        args: Vec<Vec<u8>>,
+    ) -> AppResult<T> {
+        let args = ViewFnArgs::new(AccountAddress::ONE, DELEGATION_MODULE, fun, args, vec![])?;
+        let ext_msg = Some(format!("{DELEGATION_MODULE}::{fun}"));
+        self.view_fn(args, &EdsErr::DelegationView, ext_msg).await
+    }
+}
+
+/// Reward of `active` stake for one epoch, less the operator commission
+pub fn epoch_reward(active: u128, rate: u128, denominator: u128, commission: u64) -> u128 {
+    if denominator == 0 {
+        return 0;
+    }
+    let commission = (commission as u128).min(COMMISSION_DENOMINATOR);
+    let reward = mul_div(active, rate, denominator);
+    mul_div(
+        reward,
+        COMMISSION_DENOMINATOR - commission,
+        COMMISSION_DENOMINATOR,
+    )
+}
+
+/// a * b / d rounded down, without overflowing on a * b for small b and d
+fn mul_div(a: u128, b: u128, d: u128) -> u128 {
+    a / d * b + a % d * b / d
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_node::{Endpoint, MockNode, MockResponse};
+    use std::str::FromStr;
+
+    /// Node with EDS of 8 decimals at `0x7..7` and the stake of any delegator
+    async fn staking_node(active: u128, inactive: u128, pending_inactive: u128) -> MockNode {
+        let node = MockNode::start().await;
+        node.serve_eds(AccountAddress::new([7; 32]));
+
+        let stake = (active, inactive, pending_inactive);
+        node.set_view(
+            "delegation_pool::get_stake",
+            MockResponse::view_values(3, &stake),
+        );
+        node
+    }
+
+    #[tokio::test]
+    async fn delegator_stake_from_get_stake_triple() {
+        let node = staking_node(150_000_000, 2_000_000_000, 1).await;
+        let (pool, delegator) = (AccountAddress::new([1; 32]), AccountAddress::new([2; 32]));
+
+        let stake = node
+            .client()
+            .delegator_stake(pool, delegator)
+            .await
+            .unwrap();
+        assert_eq!(stake.active, BigDecimal::from_str("1.5").unwrap());
+        assert_eq!(stake.inactive, BigDecimal::from(20));
+        assert_eq!(
+            stake.pending_inactive,
+            BigDecimal::from_str("0.00000001").unwrap()
+        );
+
+        let views = node.requests_to(Endpoint::View);
+        let get_stake = views
+            .iter()
+            .filter_map(|req| req.view_fn.as_ref())
+            .find(|f| f.function.as_str() == "get_stake")
+            .unwrap();
+        let args = vec![pool.to_bytes().unwrap(), delegator.to_bytes().unwrap()];
+        assert_eq!(get_stake.args, args);
+    }
+
+    #[tokio::test]
+    async fn epoch_reward_from_staking_config_json() {
+        let node = staking_node(100_000_000_000, 0, 0).await;
+        node.set_view(
+            "delegation_pool::operator_commission_percentage",
+            MockResponse::view(&1000u64),
+        );
+        // 1% per epoch, numbers as json strings like the node returns u64 fields
+        let config = serde_json::json!({
+            "type": STAKING_CONFIG,
+            "data": {
+                "allow_validator_set_change": true,
+                "maximum_stake": "5000000000000000",
+                "minimum_stake": "100000000000",
+                "recurring_lockup_duration_secs": "1209600",
+                "rewards_rate": "1",
+                "rewards_rate_denominator": "100",
+                "voting_power_increase_limit": "20",
+            },
+        });
+        node.set_resource(
+            AccountAddress::ONE,
+            STAKING_CONFIG,
+            MockResponse::json(config),
+        );
+
+        let (pool, delegator) = (AccountAddress::new([1; 32]), AccountAddress::new([2; 32]));
+        let client = node.client();
+        let reward = client.estimate_epoch_reward(pool, delegator).await.unwrap();
+        assert_eq!(reward, BigDecimal::from(9));
+    }
+
+    #[test]
+    fn epoch_reward_less_commission() {
+        // 1% per epoch, 10% commission
+        assert_eq!(epoch_reward(1_000_000, 1, 100, 1000), 9_000);
+        assert_eq!(epoch_reward(1_000_000, 1, 100, 0), 10_000);
+        assert_eq!(epoch_reward(1_000_000, 1, 100, 20_000), 0);
+        assert_eq!(epoch_reward(1_000_000, 1, 0, 0), 0);
+        assert_eq!(epoch_reward(u128::MAX, 1, 100, 0), u128::MAX / 100);
+    }
+}
@@ -0,0 +1,676 @@
+use crate::client::EnhancedClient;
+use crate::client::types::Token;
+use crate::error::{EdsErr, is_err_code};
+use crate::sdk_ext::events::{FaDeposit, TxnEvents};
+use crate::sdk_ext::retry::is_pruned;
+use base_infra::result::{AppResult, DynErrCode};
+use base_infra::{app_err, map_err};
+use endless_sdk::move_types::account_address::AccountAddress;
+use endless_sdk::rest_client::endless_api_types::{
+    TransactionPayload, UserTransaction, WriteSetChange,
+};
+use endless_sdk::rest_client::error::RestError;
+use endless_sdk::rest_client::{EndlessResult, Response, Transaction};
+use futures::Stream;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+use tracing::warn;
+
+/// Largest page the node serves, `max_transactions_page_size` of the node config
+pub const NODE_MAX_PAGE_SIZE: u16 = 100;
+
+/// Event handles of a fungible store, `deposit_events` is emitted on every deposit into it
+const FA_EVENTS: &str = "0x1::fungible_asset::FungibleAssetEvents";
+const DEPOSIT_EVENTS: &str = "deposit_events";
+
+const OBJECT_CORE: &str = "0x1::object::ObjectCore";
+pub(crate) const FUNGIBLE_STORE: &str = "0x1::fungible_asset::FungibleStore";
+
+/// Where to resume following an account's transactions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum TxnCursor {
+    /// Next sequence number of the account, follows transactions it sent
+    SequenceNumber(u64),
+    /// Next ledger version, follows every transaction involving the account
+    /// by scanning the whole ledger, meant for bounded version ranges
+    Version(u64),
+    /// Next event of the `deposit_events` handle of a fungible store,
+    /// follows payments into the store whoever sent them
+    Deposit {
+        store: AccountAddress,
+        sequence_number: u64,
+    },
+}
+
+impl TxnCursor {
+    /// First deposit into the primary store of `owner` for `token`
+    pub fn deposits(owner: &AccountAddress, token: Token) -> Self {
+        TxnCursor::Deposit {
+            store: token.primary_store(owner),
+            sequence_number: 0,
+        }
+    }
+}
+
+/// How an account takes part in a transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Involvement {
+    Sender,
+    /// Received a fungible asset deposit into its primary or another owned store,
+    /// named in the entry function arguments or owning a written resource
+    Receiver,
+}
+
+impl Involvement {
+    /// None when `account` is not involved, or `txn` is not a user transaction
+    pub fn of(txn: &Transaction, account: &AccountAddress) -> Option<Self> {
+        let Transaction::UserTransaction(txn) = txn else {
+            return None;
+        };
+        if txn.request.sender.inner() == account {
+            return Some(Involvement::Sender);
+        }
+
+        let named = match &txn.request.payload {
+            TransactionPayload::EntryFunctionPayload(payload) => {
+                payload.arguments.iter().any(|arg| is_address(arg, account))
+            }
+            _ => false,
+        };
+        let written = txn.info.changes.iter().any(|change| match change {
+            WriteSetChange::WriteResource(res) => res.address.inner() == account,
+            WriteSetChange::DeleteResource(res) => res.address.inner() == account,
+            _ => false,
+        });
+        (named || written || deposited_to(txn, account)).then_some(Involvement::Receiver)
+    }
+}
+
+/// Whether a deposit event of `txn` went to a fungible store of `account`,
+/// primary stores live at their own address so they are told by owner and asset
+fn deposited_to(txn: &UserTransaction, account: &AccountAddress) -> bool {
+    let stores = written_stores(&txn.info.changes);
+    let Ok(deposits) = txn.events_of::<FaDeposit>() else {
+        return false;
+    };
+    deposits.iter().any(|deposit| {
+        let written = stores.get(&deposit.store);
+        let primary = written
+            .and_then(|store| store.asset)
+            .map(|asset| Token::new(&asset).primary_store(account) == deposit.store);
+        primary == Some(true) || written.and_then(|store| store.owner) == Some(*account)
+    })
+}
+
+fn is_address(arg: &serde_json::Value, account: &AccountAddress) -> bool {
+    match arg {
+        serde_json::Value::String(s) => AccountAddress::from_str(s).is_ok_and(|a| a == *account),
+        serde_json::Value::Array(args) => args.iter().any(|arg| is_address(arg, account)),
+        _ => false,
+    }
+}
+
+/// Where a transaction stream persists its cursor between runs
+#[async_trait::async_trait]
+pub trait CursorStore: Send + Sync {
+    async fn load(&self) -> AppResult<Option<TxnCursor>>;
+
+    async fn save(&self, cursor: TxnCursor) -> AppResult<()>;
+}
+
+/// Cursor kept in memory only, the stream starts over after a restart
+#[derive(Debug, Default)]
+pub struct MemCursorStore(std::sync::Mutex<Option<TxnCursor>>);
+
+#[async_trait::async_trait]
+impl CursorStore for MemCursorStore {
+    async fn load(&self) -> AppResult<Option<TxnCursor>> {
+        Ok(*self.0.lock().expect("cursor lock"))
+    }
+
+    async fn save(&self, cursor: TxnCursor) -> AppResult<()> {
+        *self.0.lock().expect("cursor lock") = Some(cursor);
+        Ok(())
+    }
+}
+
+/// Cursor persisted as json in a file
+#[derive(Debug, Clone)]
+pub struct FileCursorStore {
+    path: PathBuf,
+}
+
+impl FileCursorStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait::async_trait]
+impl CursorStore for FileCursorStore {
+    async fn load(&self) -> AppResult<Option<TxnCursor>> {
+        let msg = self.path.display().to_string();
+        match tokio::fs::read(&self.path).await {
+            Ok(json) => serde_json::from_slice(&json)
+                .map(Some)
+                .map_err(map_err!(&EdsErr::TxnCursorStore, msg)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err).map_err(map_err!(&EdsErr::TxnCursorStore, msg)),
+        }
+    }
+
+    async fn save(&self, cursor: TxnCursor) -> AppResult<()> {
+        let msg = self.path.display().to_string();
+        let json = serde_json::to_vec(&cursor).map_err(map_err!(&EdsErr::TxnCursorStore))?;
+        // write then rename, a crash never leaves a truncated cursor behind
+        let tmp = self.path.with_extension("tmp");
+        tokio::fs::write(&tmp, json)
+            .await
+            .map_err(map_err!(&EdsErr::TxnCursorStore, msg.clone()))?;
+        tokio::fs::rename(&tmp, &self.path)
+            .await
+            .map_err(map_err!(&EdsErr::TxnCursorStore, msg))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct TxnStreamConfig {
+    /// Transactions per request, capped at `NODE_MAX_PAGE_SIZE`
+    pub page_size: u16,
+    /// Delay after the first empty poll, doubled on every further one
+    pub min_idle_delay: Duration,
+    pub max_idle_delay: Duration,
+}
+
+impl Default for TxnStreamConfig {
+    fn default() -> Self {
+        Self {
+            page_size: NODE_MAX_PAGE_SIZE,
+            min_idle_delay: Duration::from_secs(1),
+            max_idle_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl EnhancedClient {
+    /// Follow the transactions of `account` in order, from the cursor in `store`,
+    /// or from `start` when the store has none. Start from `TxnCursor::deposits`
+    /// to follow payments the account receives, from a sequence number for those it sends.
+    ///
+    /// The cursor past a yielded transaction is saved when the next one is polled,
+    /// so a transaction whose handling was interrupted is yielded again after a restart.
+    /// A cursor at versions the node pruned is moved past all of them at once,
+    /// yielding one `TxnPruned`.
+    pub fn account_txn_stream<'c, S: CursorStore + 'c>(
+        &'c self,
+        account: AccountAddress,
+        start: TxnCursor,
+        store: S,
+        config: TxnStreamConfig,
+    ) -> impl Stream<Item = AppResult<Transaction>> + 'c {
+        let state = TxnStream {
+            client: self,
+            account,
+            store,
+            page_size: config.page_size.clamp(1, NODE_MAX_PAGE_SIZE),
+            idle_delay: config.min_idle_delay,
+            config,
+            start,
+            scan: None,
+            buffer: VecDeque::new(),
+            consumed: None,
+        };
+
+        futures::stream::unfold(state, |mut state| async move {
+            let item = state.next().await;
+            Some((item, state))
+        })
+    }
+}
+
+struct TxnStream<'c, S> {
+    client: &'c EnhancedClient,
+    account: AccountAddress,
+    store: S,
+    config: TxnStreamConfig,
+    page_size: u16,
+    idle_delay: Duration,
+    start: TxnCursor,
+    /// Where the next page is fetched from, None until the stored cursor is loaded
+    scan: Option<TxnCursor>,
+    /// Fetched transactions with the cursor past each
+    buffer: VecDeque<(Transaction, TxnCursor)>,
+    /// Cursor past the last yielded transaction, not saved yet
+    consumed: Option<TxnCursor>,
+}
+
+impl<S: CursorStore> TxnStream<'_, S> {
+    async fn next(&mut self) -> AppResult<Transaction> {
+        if let Some(cursor) = self.consumed.take() {
+            self.store.save(cursor).await?;
+        }
+
+        loop {
+            if let Some((txn, cursor)) = self.buffer.pop_front() {
+                self.consumed = Some(cursor);
+                return Ok(txn);
+            }
+
+            let scan = match self.scan {
+                Some(scan) => scan,
+                None => self.store.load().await?.unwrap_or(self.start),
+            };
+            self.scan = Some(scan);
+
+            let fetched = self
+                .client
+                .account_txn_page(self.account, scan, self.page_size);
+            let (page, next_scan) = match fetched.await {
+                Ok(fetched) => fetched,
+                Err(err) if is_err_code(&err, &EdsErr::TxnPruned) => {
+                    let next = self.skip_pruned(scan).await?;
+                    let msg = format!("account[{}] skipped {scan:?} to {next:?}", self.account);
+                    warn!("txn stream of {msg}");
+                    return Err(app_err!(&EdsErr::TxnPruned, msg));
+                }
+                Err(err) => {
+                    warn!("account[{}] txn stream poll failed: {err}", self.account);
+                    self.idle().await;
+                    return Err(err);
+                }
+            };
+
+            if next_scan == scan {
+                self.idle().await;
+                continue;
+            }
+            self.idle_delay = self.config.min_idle_delay;
+            self.scan = Some(next_scan);
+
+            if page.is_empty() {
+                // nothing of the account in the scanned versions, skip them on restart
+                self.store.save(next_scan).await?;
+            }
+            self.buffer.extend(page);
+        }
+    }
+
+    /// Move the cursor past the whole pruned range in one step: a version cursor to the
+    /// oldest version the node keeps, other cursors to the first number it still serves
+    async fn skip_pruned(&mut self, scan: TxnCursor) -> AppResult<TxnCursor> {
+        let next = match scan {
+            TxnCursor::Version(version) => {
+                let oldest = self.client.get_index().await?.oldest_ledger_version;
+                TxnCursor::Version(oldest.max(version + 1))
+            }
+            _ => self.first_kept(scan).await?,
+        };
+        self.scan = Some(next);
+        self.store.save(next).await?;
+        Ok(next)
+    }
+
+    /// First cursor past the pruned `scan` the node serves, doubling the step until one is
+    /// served and then bisecting, a few requests however long the pruned range
+    async fn first_kept(&self, scan: TxnCursor) -> AppResult<TxnCursor> {
+        let number = match scan {
+            TxnCursor::SequenceNumber(seq_num) => seq_num,
+            TxnCursor::Deposit {
+                sequence_number, ..
+            } => sequence_number,
+            TxnCursor::Version(version) => version,
+        };
+        let at = |number| match scan {
+            TxnCursor::SequenceNumber(_) => TxnCursor::SequenceNumber(number),
+            TxnCursor::Deposit { store, .. } => TxnCursor::Deposit {
+                store,
+                sequence_number: number,
+            },
+            TxnCursor::Version(_) => TxnCursor::Version(number),
+        };
+
+        let (mut pruned, mut step) = (number, 1u64);
+        let mut kept = loop {
+            let probe = number.saturating_add(step);
+            if !self.is_pruned_at(at(probe)).await? {
+                break probe;
+            }
+            (pruned, step) = (probe, step.saturating_mul(2));
+        };
+        while kept - pruned > 1 {
+            let mid = pruned + (kept - pruned) / 2;
+            if self.is_pruned_at(at(mid)).await? {
+                pruned = mid;
+            } else {
+                kept = mid;
+            }
+        }
+        Ok(at(kept))
+    }
+
+    async fn is_pruned_at(&self, cursor: TxnCursor) -> AppResult<bool> {
+        match self.client.account_txn_page(self.account, cursor, 1).await {
+            Ok(_) => Ok(false),
+            Err(err) if is_err_code(&err, &EdsErr::TxnPruned) => Ok(true),
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn idle(&mut self) {
+        tokio::time::sleep(self.idle_delay).await;
+        self.idle_delay = (self.idle_delay * 2).min(self.config.max_idle_delay);
+    }
+}
+
+impl EnhancedClient {
+    /// Transactions of `account` in the page at `scan`, with the cursor past each,
+    /// and where the next page starts
+    pub(crate) async fn account_txn_page(
+        &self,
+        account: AccountAddress,
+        scan: TxnCursor,
+        limit: u16,
+    ) -> AppResult<(Vec<(Transaction, TxnCursor)>, TxnCursor)> {
+        let (client, limit) = (self.get_client(), limit.clamp(1, NODE_MAX_PAGE_SIZE));
+
+        match scan {
+            TxnCursor::SequenceNumber(seq_num) => {
+                let res = self
+                    .retry
+                    .run("get_account_transactions", || {
+                        client.get_account_transactions(account, Some(seq_num), Some(limit))
+                    })
+                    .await;
+                let msg = account.to_string();
+                let txns = map_pruned(not_found_as_empty(res), &EdsErr::GetAcctTxns, msg)?;
+
+                let mut next = scan;
+                let page = txns
+                    .into_iter()
+                    .map(|txn| {
+                        if let Transaction::UserTransaction(user_txn) = &txn {
+                            next =
+                                TxnCursor::SequenceNumber(user_txn.request.sequence_number.0 + 1);
+                        }
+                        (txn, next)
+                    })
+                    .collect();
+                Ok((page, next))
+            }
+            TxnCursor::Version(version) => {
+                let res = self
+                    .retry
+                    .run("get_transactions", || {
+                        client.get_transactions(Some(version), Some(limit))
+                    })
+                    .await;
+                let msg = format!("from version {version}");
+                let txns = map_pruned(not_found_as_empty(res), &EdsErr::GetTxns, msg)?;
+
+                let mut next = scan;
+                let page = txns
+                    .into_iter()
+                    .filter_map(|txn| {
+                        next = TxnCursor::Version(txn.version()? + 1);
+                        Involvement::of(&txn, &account).map(|_| (txn, next))
+                    })
+                    .collect();
+                Ok((page, next))
+            }
+            TxnCursor::Deposit {
+                store,
+                sequence_number,
+            } => {
+                let res = self
+                    .retry
+                    .run("get_account_events", || {
+                        let start = Some(sequence_number);
+                        client.get_account_events(
+                            store,
+                            FA_EVENTS,
+                            DEPOSIT_EVENTS,
+                            start,
+                            Some(limit),
+                        )
+                    })
+                    .await;
+                let events = not_found_as_empty(res)
+                    .map_err(map_err!(&EdsErr::GetDepositEvents, store.to_string()))?;
+
+                let (mut page, mut next): (Vec<(Transaction, TxnCursor)>, _) = (vec![], scan);
+                for event in events {
+                    let (version, sequence_number) = (event.version.0, event.sequence_number.0 + 1);
+                    let after = TxnCursor::Deposit {
+                        store,
+                        sequence_number,
+                    };
+                    match page.last_mut() {
+                        // several deposits of one transaction, it is yielded once
+                        Some((txn, cursor)) if txn.version() == Some(version) => *cursor = after,
+                        _ => match self.txn_by_version(version).await {
+                            Ok(txn) => page.push((txn, after)),
+                            // the page so far, the failed deposit is polled again
+                            Err(_) if !page.is_empty() => break,
+                            Err(err) => return Err(err),
+                        },
+                    }
+                    next = after;
+                }
+                Ok((page, next))
+            }
+        }
+    }
+
+    async fn txn_by_version(&self, version: u64) -> AppResult<Transaction> {
+        let client = self.get_client();
+        let res = self
+            .retry
+            .run("get_transaction_by_version", || {
+                client.get_transaction_by_version(version)
+            })
+            .await;
+        let msg = format!("version {version}");
+        map_pruned(res.map(Response::into_inner), &EdsErr::GetTxns, msg)
+    }
+}
+
+/// Errors of pruned versions are `TxnPruned`, others `code`
+fn map_pruned<T>(res: EndlessResult<T>, code: &'static DynErrCode, msg: String) -> AppResult<T> {
+    match res {
+        Err(err) if is_pruned(&err) => Err(err).map_err(map_err!(&EdsErr::TxnPruned, msg)),
+        res => res.map_err(map_err!(code, msg)),
+    }
+}
+
+/// Versions past the ledger, accounts and stores not created yet come back as 404
+fn not_found_as_empty<T>(res: EndlessResult<Response<Vec<T>>>) -> EndlessResult<Vec<T>> {
+    match res {
+        Ok(resp) => Ok(resp.into_inner()),
+        Err(RestError::Api(resp)) if resp.status_code.as_u16() == 404 => Ok(vec![]),
+        Err(err) => Err(err),
+    }
+}
+
+/// Owner and asset of a fungible store, as far as the write set tells
+#[derive(Debug, Default)]
+pub(crate) struct WrittenStore {
+    pub(crate) owner: Option<AccountAddress>,
+    pub(crate) asset: Option<AccountAddress>,
+}
+
+/// Owners from written `ObjectCore`s and assets from written `FungibleStore`s, by store
+pub(crate) fn written_stores(changes: &[WriteSetChange]) -> HashMap<AccountAddress, WrittenStore> {
+    let mut stores: HashMap<AccountAddress, WrittenStore> = HashMap::new();
+    for change in changes {
+        let WriteSetChange::WriteResource(res) = change else {
+            continue;
+        };
+        let Ok(resource) = serde_json::to_value(&res.data) else {
+            continue;
+        };
+        let (typ, data) = (resource["type"].as_str(), &resource["data"]);
+        let address = *res.address.inner();
+        match typ {
+            Some(OBJECT_CORE) => {
+                stores.entry(address).or_default().owner = address_of(&data["owner"])
+            }
+            Some(FUNGIBLE_STORE) => {
+                stores.entry(address).or_default().asset = address_of(&data["metadata"]["inner"])
+            }
+            _ => {}
+        }
+    }
+    stores
+}
+
+fn address_of(value: &serde_json::Value) -> Option<AccountAddress> {
+    value
+        .as_str()
+        .and_then(|s| AccountAddress::from_str(s).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::account_client::transfer_args;
+    use crate::client::history_client::TxnSummary;
+    use crate::mock_node::{MockLedger, MockNode, TxnOutcome, test_account};
+    use crate::sdk_ext::events::{FaDeposit, TxnEvents};
+    use endless_sdk::types::LocalAccount;
+    use endless_sdk::types::transaction::SignedTransaction;
+    use futures::StreamExt;
+
+    fn config() -> TxnStreamConfig {
+        TxnStreamConfig {
+            page_size: NODE_MAX_PAGE_SIZE,
+            min_idle_delay: Duration::from_millis(1),
+            max_idle_delay: Duration::from_millis(5),
+        }
+    }
+
+    async fn signed_transfer(client: &EnhancedClient, from: &LocalAccount) -> SignedTransaction {
+        let args = transfer_args(from, AccountAddress::ONE, 5, None).unwrap();
+        client.rest_client().sign_entry_fun(args).await.unwrap()
+    }
+
+    fn summaries(txns: Vec<AppResult<Transaction>>) -> Vec<TxnSummary> {
+        let txns = txns.into_iter().map(|txn| TxnSummary::of(&txn.unwrap()));
+        txns.map(Option::unwrap).collect()
+    }
+
+    #[tokio::test]
+    async fn resumes_from_file_cursor_after_restart() {
+        let node = MockNode::start().await;
+        let (client, from) = (node.client(), test_account(0x11));
+        for _ in 0..3 {
+            node.commit_txn(signed_transfer(&client, &from).await, TxnOutcome::default());
+        }
+        let path = std::env::temp_dir().join(format!("txn-cursor-{}.json", std::process::id()));
+        let (account, start) = (from.address(), TxnCursor::SequenceNumber(0));
+
+        let stream =
+            client.account_txn_stream(account, start, FileCursorStore::new(&path), config());
+        let seq_nums: Vec<u64> = summaries(stream.take(2).collect().await)
+            .iter()
+            .map(|txn| txn.sequence_number)
+            .collect();
+        assert_eq!(seq_nums, vec![0, 1]);
+        // saved past the first transaction only, the second one was never followed by a poll
+        let saved = FileCursorStore::new(&path).load().await.unwrap();
+        assert_eq!(saved, Some(TxnCursor::SequenceNumber(1)));
+
+        let stream =
+            client.account_txn_stream(account, start, FileCursorStore::new(&path), config());
+        let seq_nums: Vec<u64> = summaries(stream.take(2).collect().await)
+            .iter()
+            .map(|txn| txn.sequence_number)
+            .collect();
+        assert_eq!(seq_nums, vec![1, 2]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn follows_deposits_into_primary_store() {
+        let node = MockNode::start().await;
+        let (client, payer) = (node.client(), test_account(0x11));
+        let receiver = AccountAddress::new([2; 32]);
+        let token = Token::new(&AccountAddress::new([7; 32]));
+        let store = token.primary_store(&receiver);
+
+        let mut versions = vec![];
+        for (to, amount) in [(AccountAddress::new([3; 32]), 1), (store, 5), (store, 6)] {
+            let txn = signed_transfer(&client, &payer).await;
+            versions.push(node.commit_txn(txn, TxnOutcome::deposit(to, token.into(), amount)));
+        }
+
+        let start = TxnCursor::deposits(&receiver, token);
+        let stream =
+            client.account_txn_stream(receiver, start, MemCursorStore::default(), config());
+        let txns: Vec<Transaction> = stream.take(2).map(Result::unwrap).collect().await;
+        let deposited: Vec<(Option<u64>, u128)> = txns
+            .iter()
+            .map(|txn| {
+                (
+                    txn.version(),
+                    txn.events_of::<FaDeposit>().unwrap()[0].amount,
+                )
+            })
+            .collect();
+        assert_eq!(
+            deposited,
+            vec![(Some(versions[1]), 5), (Some(versions[2]), 6)]
+        );
+    }
+
+    #[tokio::test]
+    async fn skips_pruned_versions() {
+        let node = MockNode::start().await;
+        let (client, from) = (node.client(), test_account(0x11));
+        let version = node.commit_txn(signed_transfer(&client, &from).await, TxnOutcome::default());
+        node.set_ledger(MockLedger {
+            oldest_version: version,
+            ..node.ledger()
+        });
+
+        let (account, start) = (from.address(), TxnCursor::Version(5));
+        let stream = client.account_txn_stream(account, start, MemCursorStore::default(), config());
+        let mut items: Vec<AppResult<Transaction>> = stream.take(2).collect().await;
+        let followed = summaries(items.split_off(1));
+        let pruned = format!("{:?}", items[0].as_ref().unwrap_err());
+        assert!(
+            pruned.contains("CLT010"),
+            "expected TxnPruned, got {pruned}"
+        );
+        assert_eq!(followed[0].version, version);
+    }
+
+    #[tokio::test]
+    async fn skips_pruned_sequence_numbers_at_once() {
+        let node = MockNode::start().await;
+        let (client, from) = (node.client(), test_account(0x11));
+        let mut versions = vec![];
+        for _ in 0..6 {
+            let txn = signed_transfer(&client, &from).await;
+            versions.push(node.commit_txn(txn, TxnOutcome::default()));
+        }
+        node.set_ledger(MockLedger {
+            oldest_version: versions[4],
+            ..node.ledger()
+        });
+
+        let (account, start) = (from.address(), TxnCursor::SequenceNumber(0));
+        let stream = client.account_txn_stream(account, start, MemCursorStore::default(), config());
+        let mut items: Vec<AppResult<Transaction>> = stream.take(3).collect().await;
+        let followed = summaries(items.split_off(1));
+        let pruned = format!("{:?}", items[0].as_ref().unwrap_err());
+        assert!(
+            pruned.contains("CLT010"),
+            "expected TxnPruned, got {pruned}"
+        );
+        let seq_nums: Vec<u64> = followed.iter().map(|txn| txn.sequence_number).collect();
+        assert_eq!(seq_nums, vec![4, 5]);
+    }
+}
@@ -1,9 +1,15 @@
+use crate::sdk_ext::abort::{DecodedAbort, ErrorDesc, ErrorMaps};
 use crate::utils::bcs_ext::BcsExt;
 use base_infra::result::AppResult;
+use endless_sdk::crypto::HashValue;
 use endless_sdk::move_types::account_address::AccountAddress;
-use endless_sdk::rest_client::Response;
 use endless_sdk::rest_client::endless_api_types::IndexResponse;
+use endless_sdk::rest_client::{Response, Transaction};
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Domain separator of object addresses derived from an owner address
+const DERIVE_OBJECT_ADDRESS_SCHEME: u8 = 0xFC;
 
 #[derive(Debug, Clone, Copy)]
 pub struct Owner(AccountAddress);
@@ -33,6 +39,15 @@ impl Token {
     pub fn to_bytes(&self) -> AppResult<Vec<u8>> {
         self.0.to_bytes()
     }
+
+    /// Address of the primary fungible store of `owner` for this token,
+    /// as `object::create_user_derived_object_address` derives it
+    pub fn primary_store(&self, owner: &AccountAddress) -> AccountAddress {
+        let mut bytes = owner.to_vec();
+        bytes.extend(self.0.to_vec());
+        bytes.push(DERIVE_OBJECT_ADDRESS_SCHEME);
+        AccountAddress::new(*HashValue::sha3_256_of(&bytes))
+    }
 }
 
 impl From<Token> for AccountAddress {
@@ -43,6 +58,7 @@ impl From<Token> for AccountAddress {
 
 #[derive(Debug, Clone, Copy, Deserialize, Serialize)]
 pub struct IndexData {
+    pub chain_id: u8,
     pub epoch: u64,
     pub ledger_version: u64,
     pub oldest_ledger_version: u64,
@@ -55,6 +71,7 @@ impl From<Response<IndexResponse>> for IndexData {
     fn from(resp: Response<IndexResponse>) -> Self {
         let (idx, _state) = resp.into_parts();
         Self {
+            chain_id: idx.chain_id,
             epoch: idx.epoch.into(),
             ledger_version: idx.ledger_version.0,
             oldest_ledger_version: idx.oldest_ledger_version.0,
@@ -64,3 +81,190 @@ impl From<Response<IndexResponse>> for IndexData {
         }
     }
 }
+
+/// How `wait_for_txn_with` polls a submitted transaction
+#[derive(Debug, Clone, Copy)]
+pub struct WaitConfig {
+    /// Give up after this long, whether or not the transaction expired
+    pub timeout: Duration,
+    pub poll_interval: Duration,
+    /// Stop as soon as the ledger time passes the expiration of a transaction not committed
+    pub check_expiry: bool,
+}
+
+impl Default for WaitConfig {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(60),
+            poll_interval: Duration::from_millis(500),
+            check_expiry: true,
+        }
+    }
+}
+
+/// Vm status of a committed transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TxnStatus {
+    Success,
+    MoveAbort(DecodedAbort),
+    OutOfGas,
+    /// Any other failure, with the vm status as reported
+    VmError(String),
+}
+
+impl TxnStatus {
+    /// Aborts named by the node, or else by the bundled framework error maps
+    pub fn of(txn: &Transaction) -> Self {
+        Self::of_with(txn, ErrorMaps::framework())
+    }
+
+    pub fn of_with(txn: &Transaction, maps: &ErrorMaps) -> Self {
+        if txn.success() {
+            return Self::Success;
+        }
+        Self::parse_with(&txn.vm_status(), maps)
+    }
+
+    pub fn parse(vm_status: &str) -> Self {
+        Self::parse_with(vm_status, ErrorMaps::framework())
+    }
+
+    /// Parse the vm status of a transaction,
+    /// `Move abort in 0x1::coin: EINSUFFICIENT_BALANCE(0x10006): ...` or `Out of gas`
+    pub fn parse_with(vm_status: &str, maps: &ErrorMaps) -> Self {
+        if vm_status == "Executed successfully" {
+            return Self::Success;
+        }
+        if vm_status.eq_ignore_ascii_case("out of gas") {
+            return Self::OutOfGas;
+        }
+        match Self::parse_abort(vm_status, maps) {
+            Some(abort) => Self::MoveAbort(abort),
+            None => Self::VmError(vm_status.to_string()),
+        }
+    }
+
+    fn parse_abort(vm_status: &str, maps: &ErrorMaps) -> Option<DecodedAbort> {
+        let abort = vm_status.strip_prefix("Move abort in ")?;
+        let (location, rest) = abort.split_once(": ")?;
+        // `0x10006`, or `EINSUFFICIENT_BALANCE(0x10006): ...` when the node knows the reason
+        let (node_desc, code) = match rest.split_once('(') {
+            Some((name, code)) => {
+                let (code, description) = code.split_once(')')?;
+                let description = description.trim_start_matches(':').trim();
+                let desc = ErrorDesc {
+                    name: name.to_string(),
+                    description: description.to_string(),
+                };
+                (Some(desc), code)
+            }
+            None => (None, rest.split(':').next()?),
+        };
+        let code = u64::from_str_radix(code.trim().strip_prefix("0x")?, 16).ok()?;
+
+        // the node names the reason from the module deployed on chain, the map may be stale
+        let mut abort = maps.decode(location, code);
+        if node_desc.is_some() {
+            abort.desc = node_desc;
+        }
+        Some(abort)
+    }
+
+    pub fn is_success(&self) -> bool {
+        *self == Self::Success
+    }
+}
+
+/// Safety margin applied to simulated gas before submitting
+#[derive(Debug, Clone, Copy)]
+pub struct GasEstimateConfig {
+    /// max_gas_amount = gas_used * multiplier
+    pub multiplier: f64,
+    /// Upper bound of max_gas_amount
+    pub max_gas_cap: u64,
+}
+
+impl Default for GasEstimateConfig {
+    fn default() -> Self {
+        Self {
+            multiplier: 1.5,
+            max_gas_cap: 2_000_000,
+        }
+    }
+}
+
+impl GasEstimateConfig {
+    /// max_gas_amount for `gas_used`, None if the simulation already used more than the cap
+    pub fn max_gas_amount(&self, gas_used: u64) -> Option<u64> {
+        if gas_used > self.max_gas_cap {
+            return None;
+        }
+        let max_gas_amount = (gas_used as f64 * self.multiplier).ceil() as u64;
+        Some(max_gas_amount.clamp(gas_used, self.max_gas_cap))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn max_gas_amount_with_multiplier() {
+        let cfg = GasEstimateConfig::default();
+        assert_eq!(cfg.max_gas_amount(1000), Some(1500));
+        assert_eq!(cfg.max_gas_amount(1_999_000), Some(2_000_000));
+        assert_eq!(cfg.max_gas_amount(2_000_001), None);
+    }
+
+    #[test]
+    fn txn_status_from_vm_status() {
+        let abort = "Move abort in 0x1::coin: EINSUFFICIENT_BALANCE(0x10006): Not enough coins";
+        let TxnStatus::MoveAbort(decoded) = TxnStatus::parse(abort) else {
+            panic!("not an abort: {abort}");
+        };
+        assert_eq!(
+            (decoded.location.as_str(), decoded.code),
+            ("0x1::coin", 0x10006)
+        );
+        assert_eq!(decoded.reason_name(), Some("EINSUFFICIENT_BALANCE"));
+        assert_eq!(decoded.desc.unwrap().description, "Not enough coins");
+
+        // the reason of the node over the bundled error map
+        let renamed = "Move abort in 0x1::coin: ELOW_BALANCE(0x10006): Balance too low";
+        let TxnStatus::MoveAbort(decoded) = TxnStatus::parse(renamed) else {
+            panic!("not an abort: {renamed}");
+        };
+        assert_eq!(decoded.reason_name(), Some("ELOW_BALANCE"));
+
+        // bare code, named by the bundled error map
+        let TxnStatus::MoveAbort(decoded) = TxnStatus::parse("Move abort in 0x1::coin: 0x10006")
+        else {
+            panic!("not an abort");
+        };
+        assert_eq!(decoded.reason_name(), Some("EINSUFFICIENT_BALANCE"));
+        assert_eq!(
+            decoded.desc.unwrap().description,
+            "Not enough coins to complete transaction"
+        );
+        assert_eq!(TxnStatus::parse("Out of gas"), TxnStatus::OutOfGas);
+        assert_eq!(
+            TxnStatus::parse("Executed successfully"),
+            TxnStatus::Success
+        );
+
+        let failed = "Execution failed in 0x1::coin::transfer at code offset 3";
+        assert_eq!(
+            TxnStatus::parse(failed),
+            TxnStatus::VmError(failed.to_string())
+        );
+    }
+
+    #[test]
+    fn max_gas_amount_never_below_gas_used() {
+        let cfg = GasEstimateConfig {
+            multiplier: 0.5,
+            max_gas_cap: 10_000,
+        };
+        assert_eq!(cfg.max_gas_amount(1000), Some(1000));
+    }
+}
@@ -0,0 +1,288 @@
+//! Typed module clients generated from saved module abi json, for build scripts.
+//!
+//! ```ignore
+//! // build.rs
+//! fn main() {
+//!     let out_dir = std::env::var("OUT_DIR").unwrap();
+//!     println!("cargo:rerun-if-changed=abi");
+//!     endless_client::codegen::generate_dir("abi", out_dir).unwrap();
+//! }
+//!
+//! // src/lib.rs, for abi/locking_coin_ex.json
+//! include!(concat!(env!("OUT_DIR"), "/locking_coin_ex.rs"));
+//! // locking_coin_ex::LockingCoinExClient::new(&client).get_unlock_info(eds, staker).await?
+//! ```
+//!
+//! Generated code refers to `endless_client`, `endless_sdk`, `base_infra` and `serde`,
+//! which the including crate depends on.
+
+mod types;
+
+use crate::codegen::types::{camel_case, ident, is_signer, rust_type, struct_fields};
+use crate::error::EdsErr;
+use base_infra::map_err;
+use base_infra::result::AppResult;
+use endless_sdk::rest_client::endless_api_types::{MoveFunction, MoveModule};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+/// Generate `<module>.rs` in `out_dir` for every `*.json` abi in `abi_dir`
+pub fn generate_dir(
+    abi_dir: impl AsRef<Path>,
+    out_dir: impl AsRef<Path>,
+) -> AppResult<Vec<PathBuf>> {
+    let abi_dir = abi_dir.as_ref();
+    let entries = std::fs::read_dir(abi_dir).map_err(map_err!(
+        &EdsErr::ReadAbiFile,
+        abi_dir.display().to_string()
+    ))?;
+    let mut generated = vec![];
+    for entry in entries {
+        let path = entry
+            .map_err(map_err!(
+                &EdsErr::ReadAbiFile,
+                abi_dir.display().to_string()
+            ))?
+            .path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            let codegen = AbiCodegen::from_file(&path)?;
+            generated.push(codegen.write_to_dir(out_dir.as_ref())?);
+        }
+    }
+    Ok(generated)
+}
+
+/// Rust bindings of one move module
+pub struct AbiCodegen {
+    abi: MoveModule,
+}
+
+impl AbiCodegen {
+    pub fn new(abi: MoveModule) -> Self {
+        Self { abi }
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> AppResult<Self> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path)
+            .map_err(map_err!(&EdsErr::ReadAbiFile, path.display().to_string()))?;
+        Self::from_json(&json)
+    }
+
+    /// Abi json as saved from `/accounts/{address}/module/{name}`, or its `abi` field alone
+    pub fn from_json(json: &str) -> AppResult<Self> {
+        let mut value: serde_json::Value =
+            serde_json::from_str(json).map_err(map_err!(&EdsErr::ParseAbiJson))?;
+        if let Some(abi) = value.get_mut("abi") {
+            value = abi.take();
+        }
+        let abi = serde_json::from_value(value).map_err(map_err!(&EdsErr::ParseAbiJson))?;
+        Ok(Self::new(abi))
+    }
+
+    pub fn module_name(&self) -> &str {
+        self.abi.name.as_str()
+    }
+
+    /// Write the bindings to `<out_dir>/<module>.rs`
+    pub fn write_to_dir(&self, out_dir: &Path) -> AppResult<PathBuf> {
+        let path = out_dir.join(format!("{}.rs", self.module_name()));
+        std::fs::write(&path, self.generate())
+            .map_err(map_err!(&EdsErr::WriteBindings, path.display().to_string()))?;
+        Ok(path)
+    }
+
+    /// Bindings source: a `pub mod <module>` with the module's structs and client
+    pub fn generate(&self) -> String {
+        let (abi, module) = (&self.abi, self.module_name());
+        let client = format!("{}Client", camel_case(module));
+        let mut out = String::new();
+
+        let _ = writeln!(
+            out,
+            "/// Generated from the abi of `{}::{module}`",
+            abi.address
+        );
+        let _ = writeln!(out, "pub mod {} {{", ident(module));
+        out.push_str(HEADER);
+        let _ = writeln!(
+            out,
+            "    pub const MODULE_ADDRESS: &str = \"{}\";",
+            abi.address
+        );
+        let _ = writeln!(out, "    pub const MODULE_NAME: &str = \"{module}\";\n");
+
+        for def in &abi.structs {
+            match struct_fields(def, abi) {
+                Ok(fields) => write_struct(&mut out, def.name.as_str(), &fields),
+                Err(reason) => {
+                    let _ = writeln!(out, "    // skipped struct {}: {reason}\n", def.name);
+                }
+            }
+        }
+
+        let _ = writeln!(out, "    pub struct {client}<'c> {{");
+        out.push_str(
+            "        client: &'c EnhancedClient,\n        address: AccountAddress,\n    }\n\n",
+        );
+        let _ = writeln!(out, "    impl<'c> {client}<'c> {{");
+        out.push_str(CONSTRUCTORS);
+
+        for func in &abi.exposed_functions {
+            let res = match (func.is_entry, func.is_view) {
+                (true, _) => entry_fn(func, abi),
+                (_, true) => view_fn(func, abi),
+                _ => continue,
+            };
+            match res {
+                Ok(method) => out.push_str(&method),
+                Err(reason) => {
+                    let _ = writeln!(out, "\n        // skipped {}: {reason}", func.name);
+                }
+            }
+        }
+        out.push_str("    }\n}\n");
+        out
+    }
+}
+
+const HEADER: &str = r#"    #![allow(dead_code, unused_imports, clippy::too_many_arguments)]
+
+    use ::base_infra::result::AppResult;
+    use ::endless_client::client::EnhancedClient;
+    use ::endless_client::error::EdsErr;
+    use ::endless_client::sdk_ext::types::{EntryFnArgs, ViewFnArgs};
+    use ::endless_client::utils::bcs_ext::BcsExt;
+    use ::endless_sdk::helper_client::Overrides;
+    use ::endless_sdk::move_types::account_address::AccountAddress;
+    use ::endless_sdk::move_types::u256::U256;
+    use ::endless_sdk::rest_client::{PendingTransaction, Response};
+    use ::endless_sdk::types::LocalAccount;
+    use ::std::str::FromStr;
+
+"#;
+
+const CONSTRUCTORS: &str = r#"        /// Client of the module at the address of its abi
+        pub fn new(client: &'c EnhancedClient) -> Self {
+            let address = AccountAddress::from_str(MODULE_ADDRESS).expect("abi module address");
+            Self::with_address(client, address)
+        }
+
+        /// Client of the same module deployed at `address`
+        pub fn with_address(client: &'c EnhancedClient, address: AccountAddress) -> Self {
+            Self { client, address }
+        }
+"#;
+
+fn write_struct(out: &mut String, name: &str, fields: &[(String, String)]) {
+    out.push_str("    #[derive(Debug, Clone, ::serde::Serialize, ::serde::Deserialize)]\n");
+    let _ = writeln!(out, "    pub struct {name} {{");
+    for (field, ty) in fields {
+        let _ = writeln!(out, "        pub {field}: {ty},");
+    }
+    out.push_str("    }\n\n");
+}
+
+/// `(params, args)` of the method: typed parameters and their bcs encoding
+fn fn_args(func: &MoveFunction, abi: &MoveModule) -> Result<(String, String), String> {
+    let mut params = String::new();
+    let mut args = vec![];
+    for (i, ty) in func.params.iter().filter(|ty| !is_signer(ty)).enumerate() {
+        let _ = write!(params, ", arg{i}: {}", rust_type(ty, abi)?);
+        args.push(format!("arg{i}.to_bytes()?"));
+    }
+    if !func.generic_type_params.is_empty() {
+        params.push_str(", type_args: Vec<&str>");
+    }
+    Ok((params, args.join(", ")))
+}
+
+fn type_args(func: &MoveFunction) -> &'static str {
+    match func.generic_type_params.is_empty() {
+        true => "vec![]",
+        false => "type_args",
+    }
+}
+
+fn entry_fn(func: &MoveFunction, abi: &MoveModule) -> Result<String, String> {
+    if func.params.iter().filter(|ty| is_signer(ty)).count() > 1 {
+        return Err("multi-agent entry function".to_string());
+    }
+
+    let (name, fun) = (ident(func.name.as_str()), func.name.as_str());
+    let (params, args) = fn_args(func, abi)?;
+    let type_args = type_args(func);
+    Ok(format!(
+        r#"
+        pub async fn {name}(&self, signer: &LocalAccount{params}, overrides: Option<Overrides>) -> AppResult<Response<PendingTransaction>> {{
+            let args = vec![{args}];
+            let fn_args = EntryFnArgs::new(signer, self.address, MODULE_NAME, "{fun}", args, {type_args})?;
+            self.client.submit_entry_fn(fn_args.with_overrides(overrides)).await
+        }}
+"#
+    ))
+}
+
+fn view_fn(func: &MoveFunction, abi: &MoveModule) -> Result<String, String> {
+    let returns = func
+        .return_
+        .iter()
+        .map(|ty| rust_type(ty, abi))
+        .collect::<Result<Vec<_>, _>>()?;
+    let ret = match returns.as_slice() {
+        [] => return Err("view function without return value".to_string()),
+        [ret] => ret.clone(),
+        rets => format!("({})", rets.join(", ")),
+    };
+
+    let (name, fun) = (ident(func.name.as_str()), func.name.as_str());
+    let (params, args) = fn_args(func, abi)?;
+    let type_args = type_args(func);
+    Ok(format!(
+        r#"
+        pub async fn {name}(&self{params}) -> AppResult<{ret}> {{
+            let args = vec![{args}];
+            let fn_args = ViewFnArgs::new(self.address, MODULE_NAME, "{fun}", args, {type_args})?;
+            let ext_msg = Some(format!("{{MODULE_NAME}}::{fun}"));
+            self.client.view_fn(fn_args, &EdsErr::ViewBcsErr, ext_msg).await
+        }}
+"#
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ABI: &str = include_str!("../../tests/fixtures/locking_coin_ex.json");
+    const BINDINGS: &str = include_str!("../../tests/fixtures/locking_coin_ex.rs");
+
+    #[test]
+    fn generates_structs_and_methods() {
+        let code = AbiCodegen::from_json(ABI).unwrap().generate();
+
+        assert!(code.contains("pub mod locking_coin_ex {"));
+        assert!(code.contains("pub struct LockingCoinExClient<'c>"));
+        assert!(code.contains("pub unlock_list: Vec<UnlockAt>,"));
+        assert!(code.contains(
+            "pub async fn claim(&self, signer: &LocalAccount, arg0: AccountAddress, arg1: u128, overrides: Option<Overrides>)"
+        ));
+        assert!(code.contains("self.client.submit_entry_fn(fn_args.with_overrides(overrides))"));
+        assert!(code.contains(
+            "pub async fn get_unlock_info(&self, arg0: AccountAddress, arg1: AccountAddress) -> AppResult<UnlockInfo>"
+        ));
+    }
+
+    /// `tests/codegen.rs` compiles the fixture, it must be what the abi generates
+    #[test]
+    fn bindings_fixture_is_up_to_date() {
+        assert_eq!(AbiCodegen::from_json(ABI).unwrap().generate(), BINDINGS);
+    }
+
+    #[test]
+    fn names_and_keywords() {
+        assert_eq!(camel_case("locking_coin_ex"), "LockingCoinEx");
+        assert_eq!(ident("type"), "r#type");
+        assert_eq!(ident("amount"), "amount");
+    }
+}
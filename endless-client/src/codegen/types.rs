@@ -0,0 +1,102 @@
+use endless_sdk::rest_client::endless_api_types::{
+    MoveModule, MoveStruct, MoveStructTag, MoveType,
+};
+
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "box", "break", "const", "continue", "crate", "dyn", "else", "enum",
+    "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+    "mut", "pub", "ref", "return", "self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while", "yield",
+];
+
+/// Rust identifier of a move name, raw when it is a rust keyword
+pub(crate) fn ident(name: &str) -> String {
+    match RUST_KEYWORDS.contains(&name) {
+        true => format!("r#{name}"),
+        false => name.to_string(),
+    }
+}
+
+/// `locking_coin_ex` -> `LockingCoinEx`
+pub(crate) fn camel_case(name: &str) -> String {
+    name.split('_')
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn is_signer(ty: &MoveType) -> bool {
+    match ty {
+        MoveType::Signer => true,
+        MoveType::Reference { to, .. } => matches!(to.as_ref(), MoveType::Signer),
+        _ => false,
+    }
+}
+
+/// Rust type a move value of `ty` is bcs encoded and decoded as, or why there is none
+pub(crate) fn rust_type(ty: &MoveType, abi: &MoveModule) -> Result<String, String> {
+    Ok(match ty {
+        MoveType::Bool => "bool".to_string(),
+        MoveType::U8 => "u8".to_string(),
+        MoveType::U16 => "u16".to_string(),
+        MoveType::U32 => "u32".to_string(),
+        MoveType::U64 => "u64".to_string(),
+        MoveType::U128 => "u128".to_string(),
+        MoveType::U256 => "U256".to_string(),
+        MoveType::Address => "AccountAddress".to_string(),
+        MoveType::Vector { items } => format!("Vec<{}>", rust_type(items, abi)?),
+        MoveType::Struct(tag) => struct_type(tag, abi)?,
+        MoveType::GenericTypeParam { index } => return Err(format!("generic type T{index}")),
+        ty => return Err(format!("unsupported type {ty}")),
+    })
+}
+
+fn struct_type(tag: &MoveStructTag, abi: &MoveModule) -> Result<String, String> {
+    if tag.address == abi.address && tag.module.as_str() == abi.name.as_str() {
+        let def = abi
+            .structs
+            .iter()
+            .find(|def| def.name.as_str() == tag.name.as_str())
+            .ok_or_else(|| format!("struct {} not in abi", tag.name))?;
+        struct_fields(def, abi)?;
+        return Ok(def.name.to_string());
+    }
+
+    let (module, name) = (tag.module.as_str(), tag.name.as_str());
+    // an object is its address, whatever it points to
+    if (module, name) == ("object", "Object") {
+        return Ok("AccountAddress".to_string());
+    }
+
+    let params = tag
+        .generic_type_params
+        .iter()
+        .map(|ty| rust_type(ty, abi))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(match (module, name, params.as_slice()) {
+        ("string", "String", []) => "String".to_string(),
+        ("option", "Option", [inner]) => format!("Option<{inner}>"),
+        ("simple_map", "SimpleMap", [k, v]) => format!("Vec<({k}, {v})>"),
+        _ => return Err(format!("unsupported struct {module}::{name}")),
+    })
+}
+
+/// `(field, rust type)` of a struct of the module, or why it can not be generated
+pub(crate) fn struct_fields(
+    def: &MoveStruct,
+    abi: &MoveModule,
+) -> Result<Vec<(String, String)>, String> {
+    if def.is_native || !def.generic_type_params.is_empty() {
+        return Err(format!("native or generic struct {}", def.name));
+    }
+
+    def.fields
+        .iter()
+        .map(|field| Ok((ident(field.name.as_str()), rust_type(&field.typ, abi)?)))
+        .collect()
+}
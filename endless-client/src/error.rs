@@ -1,3 +1,5 @@
+use base_infra::result::{AppError, DynErrCode};
+
 base_infra::gen_impl_code_enum! {
     EdsErr {
         // bcs
@@ -13,6 +15,68 @@ base_infra::gen_impl_code_enum! {
         GetVersionErr = ("CLT002", "get_endless_version failed"),
         TokenBalanceOf = ("CLT003", "Get primary_fungible_store::balance failed"),
         EdsBalanceOf = ("CLT004", "Get endless_coin::balance failed"),
+        ChainIdMismatch = ("CLT005", "Chain id reported by node differs from cached chain id"),
+        EmptyClientPool = ("CLT006", "Client pool requires at least one node"),
+        GetAcctTxns = ("CLT007", "Failed to get account transactions"),
+        GetTxns = ("CLT008", "Failed to get transactions"),
+        TxnCursorStore = ("CLT009", "Failed to load or save transaction cursor"),
+        TxnPruned = ("CLT010", "Transaction is at a ledger version the node has pruned"),
+        GetDepositEvents = ("CLT011", "Failed to get deposit events of fungible store"),
+        UnboundedHistoryQuery = ("CLT012", "History query has no start for its cursor"),
+
+        // committed transaction
+        TxnMoveAbort = ("TXN001", "Transaction aborted in move"),
+        TxnOutOfGas = ("TXN002", "Transaction ran out of gas"),
+        TxnVmError = ("TXN003", "Transaction failed in vm"),
+        TxnExpired = ("TXN004", "Transaction expired before it was committed"),
+        WaitTxnTimeout = ("TXN005", "Timed out waiting for transaction"),
+
+        // fee policy
+        GasPriceOverPolicy = ("FEE001", "Gas unit price exceeds fee policy"),
+        FeeOverPolicy = ("FEE002", "Transaction fee exceeds fee policy"),
+
+        // simulation
+        SimStoreView = ("SIM001", "Failed to view fungible store of simulated transaction"),
+
+        // abort
+        ReadErrorMap = ("ABT001", "Failed to read move error map file"),
+        ParseErrorMap = ("ABT002", "Failed to parse move error map"),
+
+        // multisig
+        MultisigView = ("MSIG01", "Failed to view multisig_account"),
+
+        // locking coin
+        LockingCoinView = ("LCK001", "Failed to view locking_coin_ex"),
+
+        // staking
+        DelegationView = ("STK001", "Failed to view delegation_pool"),
+
+        // abi
+        GetModuleAbi = ("ABI001", "Failed to get module abi"),
+        AbiFnNotFound = ("ABI002", "Function not found in module abi"),
+        AbiArgArity = ("ABI003", "Argument count does not match function abi"),
+        AbiArgType = ("ABI004", "Argument does not match parameter type"),
+
+        // resources
+        ResourceNotFound = ("RES001", "Account resource not found"),
+        GetAccountResource = ("RES002", "Failed to get account resource"),
+        DecodeResource = ("RES003", "Failed to decode account resource"),
+
+        // fungible asset
+        FaSupply = ("FAM001", "Failed to get fungible asset supply"),
+        EdsMetadata = ("FAM002", "Failed to get endless_coin::get_metadata"),
+
+        // amount
+        AmountPrecision = ("AMT001", "Amount has more decimal places than the token supports"),
+        InvalidAmount = ("AMT002", "Amount is negative or exceeds u128"),
+
+        // events
+        DecodeEvent = ("EVT001", "Failed to decode event data"),
+
+        // codegen
+        ReadAbiFile = ("GEN001", "Failed to read module abi file"),
+        ParseAbiJson = ("GEN002", "Failed to parse module abi json"),
+        WriteBindings = ("GEN003", "Failed to write generated bindings"),
 
         GetIndexErr = ("SDK000", "Failed to get_index"),
         ParseIdentifier = ("SDK001", "Failed to parse to Identifier"),
@@ -22,6 +86,24 @@ base_infra::gen_impl_code_enum! {
         ViewBcsErr = ("SDK005", "Failed to view_bcs"),
         SimulateTxnErr = ("SDK006", "Failed to simulate transaction"),
         WaitForTxnErr = ("SDK007", "Failed to wait for transaction"),
+        SimulateVmFailed = ("SDK008", "Simulated transaction failed in vm"),
+        GasExceedsCap = ("SDK009", "Simulated gas used exceeds max gas cap"),
+        DecodeRawTxn = ("SDK010", "Failed to decode raw transaction bcs"),
+        DecodeSignedTxn = ("SDK011", "Failed to decode signed transaction bcs"),
+        OfflineSignerMismatch = ("SDK012", "Signer is not the sender of the raw transaction"),
+        EstimateGasPrice = ("SDK013", "Failed to estimate gas price"),
+        SignFeePayerTxn = ("SDK014", "Failed to sign fee payer transaction"),
+        SignMultiAgentTxn = ("SDK015", "Failed to sign multi agent transaction"),
+
+    }
+}
 
+/// Whether `err` was raised with the error code of `code`
+pub fn is_err_code(err: &AppError, code: &DynErrCode) -> bool {
+    match err {
+        AppError::ErrCode(err_code) | AppError::Anyhow(err_code, _) => {
+            err_code.code() == code.code()
+        }
+        _ => false,
     }
 }
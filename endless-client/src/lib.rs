@@ -1,4 +1,7 @@
 pub mod client;
+pub mod codegen;
 pub mod error;
+#[cfg(any(test, feature = "mock-node"))]
+pub mod mock_node;
 pub mod sdk_ext;
 pub mod utils;
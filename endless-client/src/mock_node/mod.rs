@@ -0,0 +1,459 @@
+//! In-process mock of the Endless REST API, for tests that run without a node.
+//!
+//! ```ignore
+//! let node = MockNode::start().await;
+//! node.set_view("endless_coin::balance", MockResponse::view(&100u128));
+//! node.enqueue(Endpoint::Submit, MockResponse::error(503, "overloaded"));
+//!
+//! let client = node.client();
+//! assert_eq!(client.balance_of(owner).await?, 100);
+//! assert_eq!(node.requests_to(Endpoint::View).len(), 1);
+//! ```
+//!
+//! Without programmed responses the node answers like a healthy chain:
+//! submitted transactions are pending for `pending_polls` lookups, then committed
+//! with the outcome set by `set_txn_outcome`. A transaction submitted again is rejected
+//! as a duplicate. Gas prices are estimated at 100, 100
+//! and 150 per level. Views and resources have no default answer. Committed transactions, also
+//! those of `commit_txn`, are listed by version, by sender and by the fungible stores
+//! they deposit into, versions below `MockLedger::oldest_version` are pruned.
+//!
+//! `test_account`, `entry_fn` and `MockNode::serve_eds` are the fixtures tests share.
+//!
+//! Built for tests of this crate, and for other crates with the `mock-node` feature.
+
+mod responses;
+mod server;
+
+pub use responses::{fa_event, store_writes};
+
+use crate::client::EnhancedClient;
+use crate::sdk_ext::account::LocalAccountExt;
+use endless_sdk::move_types::account_address::AccountAddress;
+use endless_sdk::rest_client::endless_api_types::ViewFunction;
+use endless_sdk::types::LocalAccount;
+use endless_sdk::types::transaction::{EntryFunction, SignedTransaction, TransactionPayload};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::task::JoinHandle;
+use url::Url;
+
+/// REST endpoints of the mock node, paths relative to `/v1`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Endpoint {
+    /// `GET /`
+    Index,
+    /// `POST /view`
+    View,
+    /// `POST /transactions/simulate`
+    Simulate,
+    /// `POST /transactions`
+    Submit,
+    /// `GET /transactions/by_hash/{hash}` and `/transactions/wait_by_hash/{hash}`
+    TxnByHash,
+    /// `GET /transactions/by_version/{version}`
+    TxnByVersion,
+    /// `GET /transactions`, committed transactions by version
+    Txns,
+    /// `GET /accounts/{address}`
+    Account,
+    /// `GET /accounts/{address}/resource/{type}`
+    Resource,
+    /// `GET /accounts/{address}/transactions`, committed transactions the account sent
+    AccountTxns,
+    /// `GET /accounts/{address}/events/{struct}/{field}`, fungible asset deposits into the store
+    AccountEvents,
+    /// `GET /estimate_gas_price`
+    GasPrice,
+    /// Anything else, answered with 404
+    Other,
+}
+
+/// Response programmed for the next request to an endpoint
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: Vec<u8>,
+}
+
+impl MockResponse {
+    pub fn json(value: serde_json::Value) -> Self {
+        Self {
+            status: 200,
+            content_type: "application/json",
+            body: value.to_string().into_bytes(),
+        }
+    }
+
+    pub fn bcs(body: Vec<u8>) -> Self {
+        Self {
+            status: 200,
+            content_type: "application/x-bcs",
+            body,
+        }
+    }
+
+    /// Bcs body of a view function returning the single value `value`
+    pub fn view<T: Serialize>(value: &T) -> Self {
+        Self::view_values(1, value)
+    }
+
+    /// Bcs body of a view function returning `count` values, `values` a tuple of them
+    pub fn view_values<T: Serialize>(count: u8, values: &T) -> Self {
+        Self::bcs(bcs::to_bytes(&(count, values)).expect("bcs of view values"))
+    }
+
+    /// Error body of the node, with the usual `error_code` of `status`
+    pub fn error(status: u16, message: &str) -> Self {
+        let error_code = match status {
+            400 => "invalid_input",
+            404 => "resource_not_found",
+            410 => "version_pruned",
+            _ => "internal_error",
+        };
+        Self::api_error(status, error_code, message)
+    }
+
+    /// Error body of the node with `error_code`, `account_not_found` or `version_not_found`
+    pub fn api_error(status: u16, error_code: &str, message: &str) -> Self {
+        let body = serde_json::json!({
+            "message": message,
+            "error_code": error_code,
+            "vm_error_code": null,
+        });
+        Self::json(body).with_status(status)
+    }
+
+    /// Validation rejection of a submitted or simulated transaction with vm status `vm_error_code`
+    pub fn vm_error(message: &str, vm_error_code: u64) -> Self {
+        let body = serde_json::json!({
+            "message": message,
+            "error_code": "vm_error",
+            "vm_error_code": vm_error_code,
+        });
+        Self::json(body).with_status(400)
+    }
+
+    pub fn with_status(self, status: u16) -> Self {
+        Self { status, ..self }
+    }
+}
+
+/// Request received by the mock node, with its bcs body decoded where the endpoint takes one
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub endpoint: Endpoint,
+    pub method: String,
+    /// Path relative to `/v1`
+    pub path: String,
+    pub query: Option<String>,
+    pub body: Vec<u8>,
+    /// Simulated or submitted transaction
+    pub signed_txn: Option<SignedTransaction>,
+    pub view_fn: Option<ViewFunction>,
+}
+
+/// Ledger state reported by `/` and the state headers of every response
+#[derive(Debug, Clone, Copy)]
+pub struct MockLedger {
+    pub chain_id: u8,
+    pub epoch: u64,
+    pub version: u64,
+    pub block_height: u64,
+    pub timestamp_usecs: u64,
+    /// Versions below are pruned
+    pub oldest_version: u64,
+}
+
+impl Default for MockLedger {
+    fn default() -> Self {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time")
+            .as_micros() as u64;
+        Self {
+            chain_id: 4,
+            epoch: 1,
+            version: 1000,
+            block_height: 100,
+            timestamp_usecs: now,
+            oldest_version: 0,
+        }
+    }
+}
+
+/// How committed and simulated transactions end
+#[derive(Debug, Clone)]
+pub struct TxnOutcome {
+    pub success: bool,
+    pub vm_status: String,
+    pub gas_used: u64,
+    /// Json events as the node returns them
+    pub events: Vec<serde_json::Value>,
+    /// Json write set changes as the node returns them
+    pub changes: Vec<serde_json::Value>,
+}
+
+impl TxnOutcome {
+    /// Successful outcome depositing `amount` of `asset` into the fungible store `store`
+    pub fn deposit(store: AccountAddress, asset: AccountAddress, amount: u128) -> Self {
+        Self {
+            events: vec![responses::fa_event("Deposit", store, amount)],
+            changes: responses::store_writes(store, None, asset),
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for TxnOutcome {
+    fn default() -> Self {
+        Self {
+            success: true,
+            vm_status: "Executed successfully".to_string(),
+            gas_used: 10,
+            events: vec![],
+            changes: vec![],
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct MockState {
+    ledger: MockLedger,
+    outcome: TxnOutcome,
+    pending_polls: usize,
+    queued: HashMap<Endpoint, VecDeque<MockResponse>>,
+    /// Requests handled as usual but answered with 503, by endpoint
+    dropped: HashMap<Endpoint, usize>,
+    /// By `module::function`
+    views: HashMap<String, MockResponse>,
+    account_seq_nums: HashMap<AccountAddress, u64>,
+    /// By account and `0x1::module::Struct`
+    resources: HashMap<(AccountAddress, String), MockResponse>,
+    /// Submitted transactions by hash
+    txns: HashMap<String, MockTxn>,
+    /// Committed transactions by version
+    committed: BTreeMap<u64, (SignedTransaction, TxnOutcome)>,
+    requests: Vec<RecordedRequest>,
+}
+
+/// A submitted transaction, committed once its pending lookups are used up
+pub(crate) struct MockTxn {
+    signed_txn: SignedTransaction,
+    pending_polls: usize,
+    /// Version and outcome, fixed by the first lookup after it committed
+    committed: Option<(u64, TxnOutcome)>,
+}
+
+pub struct MockNode {
+    addr: SocketAddr,
+    state: Arc<Mutex<MockState>>,
+    server: JoinHandle<()>,
+}
+
+impl MockNode {
+    /// Serve on a free local port until dropped
+    pub async fn start() -> Self {
+        let state = Arc::new(Mutex::new(MockState::default()));
+        let (addr, server) = server::serve(state.clone()).await;
+        Self {
+            addr,
+            state,
+            server,
+        }
+    }
+
+    /// Node url including `/v1`
+    pub fn url(&self) -> Url {
+        Url::parse(&format!("http://{}/v1", self.addr)).expect("mock node url")
+    }
+
+    pub fn client(&self) -> EnhancedClient {
+        EnhancedClient::new(self.url())
+    }
+
+    /// Answer the next request to `endpoint` with `response`, before any default behavior
+    pub fn enqueue(&self, endpoint: Endpoint, response: MockResponse) {
+        let mut state = self.state();
+        state
+            .queued
+            .entry(endpoint)
+            .or_default()
+            .push_back(response);
+    }
+
+    /// Handle the next request to `endpoint` as usual but answer 503, as if the response was lost
+    pub fn drop_next(&self, endpoint: Endpoint) {
+        *self.state().dropped.entry(endpoint).or_default() += 1;
+    }
+
+    /// Answer every call of the view function `module::function`
+    pub fn set_view(&self, function: &str, response: MockResponse) {
+        self.state().views.insert(function.to_string(), response);
+    }
+
+    pub fn set_ledger(&self, ledger: MockLedger) {
+        self.state().ledger = ledger;
+    }
+
+    pub fn ledger(&self) -> MockLedger {
+        self.state().ledger
+    }
+
+    /// Outcome of transactions simulated or committed from now on
+    pub fn set_txn_outcome(&self, outcome: TxnOutcome) {
+        self.state().outcome = outcome;
+    }
+
+    /// Lookups a submitted transaction stays pending for, 0 by default
+    pub fn set_pending_polls(&self, pending_polls: usize) {
+        self.state().pending_polls = pending_polls;
+    }
+
+    /// Commit `txn` with `outcome` at the next ledger version, as if submitted elsewhere
+    pub fn commit_txn(&self, txn: SignedTransaction, outcome: TxnOutcome) -> u64 {
+        let mut state = self.state();
+        state.ledger.version += 1;
+        let version = state.ledger.version;
+        state.committed.insert(version, (txn, outcome));
+        version
+    }
+
+    /// Answer every read of the resource `resource_type` (`0x1::module::Struct`) of `address`,
+    /// json or bcs as the response is built
+    pub fn set_resource(
+        &self,
+        address: AccountAddress,
+        resource_type: &str,
+        response: MockResponse,
+    ) {
+        let key = (address, resource_type.to_string());
+        self.state().resources.insert(key, response);
+    }
+
+    /// Sequence number reported by `/accounts/{address}`, 0 for unknown accounts
+    pub fn set_account_seq_num(&self, address: AccountAddress, seq_num: u64) {
+        self.state().account_seq_nums.insert(address, seq_num);
+    }
+
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.state().requests.clone()
+    }
+
+    pub fn requests_to(&self, endpoint: Endpoint) -> Vec<RecordedRequest> {
+        let state = self.state();
+        let requests = state.requests.iter().filter(|req| req.endpoint == endpoint);
+        requests.cloned().collect()
+    }
+
+    /// Transactions submitted so far, in order
+    pub fn submitted_txns(&self) -> Vec<SignedTransaction> {
+        let requests = self.requests_to(Endpoint::Submit);
+        requests
+            .into_iter()
+            .filter_map(|req| req.signed_txn)
+            .collect()
+    }
+
+    /// Serve EDS of 8 decimals at `eds`, its metadata address view and `Metadata` resource
+    pub fn serve_eds(&self, eds: AccountAddress) {
+        self.set_view("endless_coin::get_metadata", MockResponse::view(&eds));
+        // name, symbol, decimals, icon_uri, project_uri
+        let metadata = ("Endless Coin", "EDS", 8u8, "", "");
+        let metadata = MockResponse::bcs(bcs::to_bytes(&metadata).expect("bcs of metadata"));
+        self.set_resource(eds, "0x1::fungible_asset::Metadata", metadata);
+    }
+
+    fn state(&self) -> MutexGuard<'_, MockState> {
+        self.state.lock().expect("mock node state")
+    }
+}
+
+/// Account of the private key of 32 `byte`s, the same in every test
+pub fn test_account(byte: u8) -> LocalAccount {
+    let key = hex::encode([byte; 32]);
+    key.as_str().recover_account().expect("test account key")
+}
+
+/// Entry function of a transaction payload, panicking on any other payload
+pub fn entry_fn(payload: &TransactionPayload) -> &EntryFunction {
+    match payload {
+        TransactionPayload::EntryFunction(entry_fn) => entry_fn,
+        other => panic!("expected an entry function, got {other:?}"),
+    }
+}
+
+impl Drop for MockNode {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::account_client::AcctClientTrait;
+    use crate::client::types::Owner;
+
+    #[tokio::test]
+    async fn view_with_decoded_args() {
+        let node = MockNode::start().await;
+        node.set_view("endless_coin::balance", MockResponse::view(&1234u128));
+
+        let owner = AccountAddress::ONE;
+        let balance = node.client().balance_of(Owner::new(&owner)).await.unwrap();
+        assert_eq!(balance, 1234);
+
+        let view_fn = node.requests_to(Endpoint::View)[0].view_fn.clone().unwrap();
+        assert_eq!(view_fn.function.as_str(), "balance");
+        assert_eq!(view_fn.args, vec![bcs::to_bytes(&owner).unwrap()]);
+    }
+
+    #[tokio::test]
+    async fn transfer_waits_until_committed() {
+        let node = MockNode::start().await;
+        node.set_pending_polls(1);
+
+        let from = test_account(0x11);
+        let client = node.client();
+        let txn = client.transfer_wait_txn(&from, AccountAddress::ONE, 5, None);
+        let txn = txn.await.unwrap().into_inner();
+        assert!(txn.success());
+
+        // committed once, at one version
+        let hash = txn.transaction_info().unwrap().hash;
+        let again = client.get_client().get_transaction_by_hash(hash.into());
+        assert_eq!(again.await.unwrap().inner().version(), txn.version());
+
+        let submitted = node.submitted_txns();
+        assert_eq!(submitted.len(), 1);
+        assert_eq!(submitted[0].sender(), from.address());
+        assert_eq!(submitted[0].sequence_number(), 0);
+        assert!(node.requests_to(Endpoint::TxnByHash).len() >= 2);
+    }
+
+    #[tokio::test]
+    async fn failed_txn_outcome() {
+        let node = MockNode::start().await;
+        node.set_txn_outcome(TxnOutcome {
+            success: false,
+            vm_status: "Move abort in 0x1::endless_account: 0x10001".to_string(),
+            gas_used: 5,
+            ..TxnOutcome::default()
+        });
+
+        let from = test_account(0x11);
+        let client = node.client();
+        let sim = client.simulate_transfer(&from, AccountAddress::ONE, 5, None);
+        assert!(!sim.await.unwrap().inner()[0].info.success);
+
+        let transfer = client.transfer(&from, AccountAddress::ONE, 5, None);
+        let hash = transfer.await.unwrap().into_inner().hash;
+        let committed = client.get_client().get_transaction_by_hash(hash.into());
+        assert!(!committed.await.unwrap().inner().success());
+    }
+}
@@ -0,0 +1,211 @@
+//! Json bodies of the node, built from the mock ledger and the transactions it received
+
+use crate::mock_node::{MockLedger, TxnOutcome};
+use endless_sdk::move_types::account_address::AccountAddress;
+use endless_sdk::move_types::language_storage::TypeTag;
+use endless_sdk::types::transaction::{
+    EntryFunction, MultisigTransactionPayload, SignedTransaction, TransactionPayload,
+};
+use serde_json::{Value, json};
+
+const ZERO_HASH: &str = "0x0000000000000000000000000000000000000000000000000000000000000000";
+
+pub(super) fn index(ledger: &MockLedger) -> Value {
+    json!({
+        "chain_id": ledger.chain_id,
+        "epoch": ledger.epoch.to_string(),
+        "ledger_version": ledger.version.to_string(),
+        "oldest_ledger_version": ledger.oldest_version.to_string(),
+        "ledger_timestamp": ledger.timestamp_usecs.to_string(),
+        "node_role": "full_node",
+        "oldest_block_height": "0",
+        "block_height": ledger.block_height.to_string(),
+        "git_hash": null,
+    })
+}
+
+pub(super) fn account(seq_num: u64) -> Value {
+    json!({
+        "sequence_number": seq_num.to_string(),
+        "authentication_key": ZERO_HASH,
+    })
+}
+
+/// Gas unit prices of a quiet network
+pub(super) fn gas_estimation() -> Value {
+    json!({
+        "deprioritized_gas_estimate": 100,
+        "gas_estimate": 100,
+        "prioritized_gas_estimate": 150,
+    })
+}
+
+pub(super) fn txn_hash(txn: &SignedTransaction) -> String {
+    txn.clone().committed_hash().to_hex_literal()
+}
+
+/// `pending_transaction` of `txn`
+pub(super) fn pending_txn(txn: &SignedTransaction) -> Value {
+    let mut pending = txn_request(txn);
+    pending["type"] = json!("pending_transaction");
+    pending["hash"] = json!(txn_hash(txn));
+    pending
+}
+
+/// `user_transaction` of `txn` committed at `version` with `outcome`
+pub(super) fn user_txn(
+    txn: &SignedTransaction,
+    version: u64,
+    ledger: &MockLedger,
+    outcome: &TxnOutcome,
+) -> Value {
+    let mut user_txn = txn_request(txn);
+    let committed = json!({
+        "type": "user_transaction",
+        "version": version.to_string(),
+        "hash": txn_hash(txn),
+        "state_change_hash": ZERO_HASH,
+        "event_root_hash": ZERO_HASH,
+        "state_checkpoint_hash": null,
+        "accumulator_root_hash": ZERO_HASH,
+        "gas_used": outcome.gas_used.to_string(),
+        "success": outcome.success,
+        "vm_status": outcome.vm_status,
+        "changes": outcome.changes,
+        "events": outcome.events,
+        "timestamp": ledger.timestamp_usecs.to_string(),
+    });
+    for (key, value) in committed.as_object().expect("json object") {
+        user_txn[key] = value.clone();
+    }
+    user_txn
+}
+
+/// `0x1::fungible_asset::{kind}` module event, `Withdraw` or `Deposit`
+pub fn fa_event(kind: &str, store: AccountAddress, amount: u128) -> Value {
+    json!({
+        "guid": {
+            "creation_number": "0",
+            "account_address": AccountAddress::ZERO.to_string(),
+        },
+        "sequence_number": "0",
+        "type": format!("0x1::fungible_asset::{kind}"),
+        "data": { "store": store.to_string(), "amount": amount.to_string() },
+    })
+}
+
+/// Event of the `deposit_events` handle of a fungible store, emitted at `version`
+pub(super) fn deposit_event(
+    store: AccountAddress,
+    version: u64,
+    sequence_number: u64,
+    amount: &Value,
+) -> Value {
+    json!({
+        "version": version.to_string(),
+        "guid": {
+            "creation_number": "0",
+            "account_address": store.to_string(),
+        },
+        "sequence_number": sequence_number.to_string(),
+        "type": "0x1::fungible_asset::DepositEvent",
+        "data": { "amount": amount },
+    })
+}
+
+/// Writes of a fungible store of `asset`, with its `ObjectCore` when `owner` is set
+pub fn store_writes(
+    store: AccountAddress,
+    owner: Option<AccountAddress>,
+    asset: AccountAddress,
+) -> Vec<Value> {
+    let write = |typ: &str, data: Value| {
+        json!({
+            "type": "write_resource",
+            "address": store.to_string(),
+            "state_key_hash": ZERO_HASH,
+            "data": { "type": typ, "data": data },
+        })
+    };
+    let fungible_store = json!({
+        "metadata": { "inner": asset.to_string() },
+        "balance": "0",
+        "frozen": false,
+    });
+    let mut writes = vec![write("0x1::fungible_asset::FungibleStore", fungible_store)];
+    if let Some(owner) = owner {
+        let object_core = json!({
+            "guid_creation_num": "0",
+            "owner": owner.to_string(),
+            "allow_ungated_transfer": false,
+        });
+        writes.push(write("0x1::object::ObjectCore", object_core));
+    }
+    writes
+}
+
+/// Fields of the transaction as signed, the signature left out
+fn txn_request(txn: &SignedTransaction) -> Value {
+    json!({
+        "sender": txn.sender().to_string(),
+        "sequence_number": txn.sequence_number().to_string(),
+        "max_gas_amount": txn.max_gas_amount().to_string(),
+        "gas_unit_price": txn.gas_unit_price().to_string(),
+        "expiration_timestamp_secs": txn.expiration_timestamp_secs().to_string(),
+        "payload": payload(txn.payload()),
+    })
+}
+
+fn payload(payload: &TransactionPayload) -> Value {
+    match payload {
+        TransactionPayload::EntryFunction(entry_fn) => entry_fn_payload(entry_fn),
+        TransactionPayload::Multisig(multisig) => {
+            let txn_payload = multisig
+                .transaction_payload
+                .as_ref()
+                .map(|payload| match payload {
+                    MultisigTransactionPayload::EntryFunction(entry_fn) => {
+                        entry_fn_payload(entry_fn)
+                    }
+                });
+            json!({
+                "type": "multisig_payload",
+                "multisig_address": multisig.multisig_address.to_string(),
+                "transaction_payload": txn_payload,
+            })
+        }
+        _ => json!({
+            "type": "script_payload",
+            "code": { "bytecode": "0x" },
+            "type_arguments": [],
+            "arguments": [],
+        }),
+    }
+}
+
+/// Arguments are shown as the hex of their bcs, the node would decode them by the abi
+fn entry_fn_payload(entry_fn: &EntryFunction) -> Value {
+    let module = entry_fn.module();
+    let function = format!(
+        "{}::{}::{}",
+        module.address(),
+        module.name(),
+        entry_fn.function()
+    );
+    let type_args: Vec<String> = entry_fn
+        .ty_args()
+        .iter()
+        .map(TypeTag::to_canonical_string)
+        .collect();
+    let args: Vec<String> = entry_fn
+        .args()
+        .iter()
+        .map(|arg| format!("0x{}", hex::encode(arg)))
+        .collect();
+    json!({
+        "type": "entry_function_payload",
+        "function": function,
+        "type_arguments": type_args,
+        "arguments": args,
+    })
+}
@@ -0,0 +1,299 @@
+use crate::mock_node::responses;
+use crate::mock_node::{Endpoint, MockLedger, MockResponse, MockState, MockTxn, RecordedRequest};
+use axum::Router;
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{HeaderName, HeaderValue, Method, StatusCode, Uri, header};
+use axum::response::{IntoResponse, Response};
+use endless_sdk::move_types::account_address::AccountAddress;
+use endless_sdk::rest_client::endless_api_types::ViewFunction;
+use endless_sdk::types::transaction::SignedTransaction;
+use std::collections::VecDeque;
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+type SharedState = Arc<Mutex<MockState>>;
+
+pub(super) async fn serve(state: SharedState) -> (SocketAddr, JoinHandle<()>) {
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind mock node");
+    let addr = listener.local_addr().expect("mock node address");
+
+    let app = Router::new().fallback(handle).with_state(state);
+    let server = tokio::spawn(async move {
+        axum::serve(listener, app).await.expect("serve mock node");
+    });
+    (addr, server)
+}
+
+async fn handle(
+    State(state): State<SharedState>,
+    method: Method,
+    uri: Uri,
+    body: Bytes,
+) -> Response {
+    let path = uri.path().strip_prefix("/v1").unwrap_or(uri.path());
+    let path = path.trim_end_matches('/').to_string();
+    let endpoint = endpoint_of(&method, &path);
+
+    let signed_txn = match endpoint {
+        Endpoint::Simulate | Endpoint::Submit => bcs::from_bytes::<SignedTransaction>(&body).ok(),
+        _ => None,
+    };
+    let view_fn = match endpoint {
+        Endpoint::View => bcs::from_bytes::<ViewFunction>(&body).ok(),
+        _ => None,
+    };
+
+    let mut state = state.lock().expect("mock node state");
+    state.requests.push(RecordedRequest {
+        endpoint,
+        method: method.to_string(),
+        path: path.clone(),
+        query: uri.query().map(str::to_string),
+        body: body.to_vec(),
+        signed_txn: signed_txn.clone(),
+        view_fn: view_fn.clone(),
+    });
+
+    let queued = state
+        .queued
+        .get_mut(&endpoint)
+        .and_then(VecDeque::pop_front);
+    let response = match queued {
+        Some(response) => response,
+        None => respond(
+            &mut state,
+            endpoint,
+            &path,
+            uri.query(),
+            signed_txn,
+            view_fn,
+        ),
+    };
+    let response = match state.dropped.get_mut(&endpoint) {
+        Some(dropped) if *dropped > 0 => {
+            *dropped -= 1;
+            MockResponse::error(503, "response lost")
+        }
+        _ => response,
+    };
+    into_response(&state.ledger, response)
+}
+
+fn endpoint_of(method: &Method, path: &str) -> Endpoint {
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    match (method.as_str(), segments.as_slice()) {
+        ("GET", []) => Endpoint::Index,
+        ("POST", ["view"]) => Endpoint::View,
+        ("POST", ["transactions", "simulate"]) => Endpoint::Simulate,
+        ("POST", ["transactions"]) => Endpoint::Submit,
+        ("GET", ["transactions", "by_hash" | "wait_by_hash", _]) => Endpoint::TxnByHash,
+        ("GET", ["transactions", "by_version", _]) => Endpoint::TxnByVersion,
+        ("GET", ["transactions"]) => Endpoint::Txns,
+        ("GET", ["accounts", _]) => Endpoint::Account,
+        ("GET", ["accounts", _, "resource", _]) => Endpoint::Resource,
+        ("GET", ["accounts", _, "transactions"]) => Endpoint::AccountTxns,
+        ("GET", ["accounts", _, "events", _, _]) => Endpoint::AccountEvents,
+        ("GET", ["estimate_gas_price"]) => Endpoint::GasPrice,
+        _ => Endpoint::Other,
+    }
+}
+
+/// Default behavior of an endpoint without a queued response
+fn respond(
+    state: &mut MockState,
+    endpoint: Endpoint,
+    path: &str,
+    query: Option<&str>,
+    signed_txn: Option<SignedTransaction>,
+    view_fn: Option<ViewFunction>,
+) -> MockResponse {
+    let last_segment = path.rsplit('/').next().unwrap_or_default();
+    let account = path
+        .split('/')
+        .nth(2)
+        .and_then(|s| AccountAddress::from_str(s).ok());
+    let (start, limit) = (param(query, "start"), param(query, "limit").unwrap_or(25));
+    match endpoint {
+        Endpoint::Index => MockResponse::json(responses::index(&state.ledger)),
+        Endpoint::View => {
+            let function = view_fn.map(|f| format!("{}::{}", f.module.name(), f.function));
+            match function.and_then(|function| state.views.get(&function)) {
+                Some(response) => response.clone(),
+                None => MockResponse::error(400, "view function not programmed"),
+            }
+        }
+        Endpoint::Simulate => match signed_txn {
+            Some(txn) => {
+                let version = state.ledger.version;
+                let user_txn = responses::user_txn(&txn, version, &state.ledger, &state.outcome);
+                MockResponse::json(serde_json::json!([user_txn]))
+            }
+            None => MockResponse::error(400, "invalid signed transaction bcs"),
+        },
+        Endpoint::Submit => match signed_txn {
+            Some(txn) => {
+                let pending = responses::pending_txn(&txn);
+                let hash = responses::txn_hash(&txn);
+                match state.txns.get(&hash) {
+                    Some(known) if known.committed.is_some() => {
+                        let msg = "Invalid transaction: SEQUENCE_NUMBER_TOO_OLD";
+                        return MockResponse::vm_error(msg, 3);
+                    }
+                    Some(_) => return MockResponse::error(400, "Transaction already in mempool"),
+                    None => {}
+                }
+                let mock_txn = MockTxn {
+                    signed_txn: txn,
+                    pending_polls: state.pending_polls,
+                    committed: None,
+                };
+                state.txns.insert(hash, mock_txn);
+                MockResponse::json(pending).with_status(202)
+            }
+            None => MockResponse::error(400, "invalid signed transaction bcs"),
+        },
+        Endpoint::TxnByHash => {
+            let MockState {
+                txns,
+                ledger,
+                outcome,
+                committed,
+                ..
+            } = state;
+            match txns.get_mut(last_segment) {
+                Some(txn) if txn.pending_polls > 0 => {
+                    txn.pending_polls -= 1;
+                    MockResponse::json(responses::pending_txn(&txn.signed_txn))
+                }
+                Some(txn) => {
+                    if txn.committed.is_none() {
+                        ledger.version += 1;
+                        txn.committed = Some((ledger.version, outcome.clone()));
+                        let committed_txn = (txn.signed_txn.clone(), outcome.clone());
+                        committed.insert(ledger.version, committed_txn);
+                    }
+                    let (version, outcome) = txn.committed.as_ref().expect("committed txn");
+                    let user_txn = responses::user_txn(&txn.signed_txn, *version, ledger, outcome);
+                    MockResponse::json(user_txn)
+                }
+                None => MockResponse::error(404, "transaction not found"),
+            }
+        }
+        Endpoint::TxnByVersion => {
+            let version = last_segment.parse().unwrap_or_default();
+            match state.committed.get(&version) {
+                _ if version < state.ledger.oldest_version => pruned(version),
+                Some((txn, outcome)) => {
+                    let user_txn = responses::user_txn(txn, version, &state.ledger, outcome);
+                    MockResponse::json(user_txn)
+                }
+                None => MockResponse::error(404, "transaction not found"),
+            }
+        }
+        Endpoint::Txns => {
+            let start = start.unwrap_or_default();
+            if start < state.ledger.oldest_version {
+                return pruned(start);
+            }
+            let txns = state.committed.range(start..).take(limit as usize);
+            let txns = txns.map(|(version, (txn, outcome))| {
+                responses::user_txn(txn, *version, &state.ledger, outcome)
+            });
+            MockResponse::json(serde_json::json!(txns.collect::<Vec<_>>()))
+        }
+        Endpoint::AccountTxns => {
+            let start = start.unwrap_or_default();
+            let sent = state.committed.iter().filter(|(_, (txn, _))| {
+                Some(txn.sender()) == account && txn.sequence_number() >= start
+            });
+            match sent.clone().next() {
+                Some((version, _)) if *version < state.ledger.oldest_version => {
+                    return pruned(*version);
+                }
+                _ => {}
+            }
+            let txns = sent.take(limit as usize).map(|(version, (txn, outcome))| {
+                responses::user_txn(txn, *version, &state.ledger, outcome)
+            });
+            MockResponse::json(serde_json::json!(txns.collect::<Vec<_>>()))
+        }
+        Endpoint::AccountEvents => {
+            let Some(store) = account else {
+                return MockResponse::error(400, "invalid account address");
+            };
+            let deposits = state.committed.iter().flat_map(|(version, (_, outcome))| {
+                let events = outcome.events.iter().filter(move |event| {
+                    event["type"] == "0x1::fungible_asset::Deposit"
+                        && event["data"]["store"] == store.to_string()
+                });
+                events.map(|event| (*version, &event["data"]["amount"]))
+            });
+            let events = deposits
+                .enumerate()
+                .skip(start.unwrap_or_default() as usize);
+            let events = events
+                .take(limit as usize)
+                .map(|(seq_num, (version, amount))| {
+                    responses::deposit_event(store, version, seq_num as u64, amount)
+                });
+            MockResponse::json(serde_json::json!(events.collect::<Vec<_>>()))
+        }
+        Endpoint::Account => {
+            let address = AccountAddress::from_str(last_segment).ok();
+            let seq_num = address.and_then(|address| state.account_seq_nums.get(&address));
+            let seq_num = seq_num.copied();
+            MockResponse::json(responses::account(seq_num.unwrap_or_default()))
+        }
+        Endpoint::Resource => {
+            let resource_type = last_segment.replace("%3A", ":").replace("%3a", ":");
+            let key = account.map(|account| (account, resource_type));
+            match key.and_then(|key| state.resources.get(&key)) {
+                Some(response) => response.clone(),
+                None => MockResponse::error(404, "resource not found"),
+            }
+        }
+        Endpoint::GasPrice => MockResponse::json(responses::gas_estimation()),
+        Endpoint::Other => MockResponse::error(404, "not found"),
+    }
+}
+
+fn pruned(version: u64) -> MockResponse {
+    MockResponse::error(410, &format!("Ledger version({version}) has been pruned"))
+}
+
+/// Numeric query parameter `name`
+fn param(query: Option<&str>, name: &str) -> Option<u64> {
+    let mut pairs = query?.split('&');
+    pairs.find_map(|pair| pair.strip_prefix(name)?.strip_prefix('=')?.parse().ok())
+}
+
+/// Response with the ledger state headers the rest client reads
+fn into_response(ledger: &MockLedger, response: MockResponse) -> Response {
+    let state_headers = [
+        ("x-endless-chain-id", ledger.chain_id as u64),
+        ("x-endless-ledger-version", ledger.version),
+        ("x-endless-ledger-oldest-version", ledger.oldest_version),
+        ("x-endless-ledger-timestampusec", ledger.timestamp_usecs),
+        ("x-endless-epoch", ledger.epoch),
+        ("x-endless-block-height", ledger.block_height),
+        ("x-endless-oldest-block-height", 0),
+    ];
+
+    let status = StatusCode::from_u16(response.status).expect("mock response status");
+    let mut resp = (status, response.body).into_response();
+    let headers = resp.headers_mut();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static(response.content_type),
+    );
+    for (name, value) in state_headers {
+        headers.insert(HeaderName::from_static(name), HeaderValue::from(value));
+    }
+    resp
+}
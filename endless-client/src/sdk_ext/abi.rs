@@ -0,0 +1,380 @@
+use crate::error::EdsErr;
+use base_infra::app_err;
+use base_infra::result::AppResult;
+use endless_sdk::move_types::account_address::AccountAddress;
+use endless_sdk::move_types::language_storage::TypeTag;
+use endless_sdk::move_types::u256::U256;
+use endless_sdk::rest_client::endless_api_types::{
+    MoveFunction, MoveModule, MoveStructTag, MoveType,
+};
+use moka::future::Cache;
+use serde::Serialize;
+use serde_json::Value;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+static MODULE_ABI_CACHE: OnceLock<Cache<String, MoveModule>> = OnceLock::new();
+
+/// Module abi cache keyed by node url and module id
+pub(crate) struct ModuleAbiCache;
+impl ModuleAbiCache {
+    fn cache(&self) -> &'static Cache<String, MoveModule> {
+        MODULE_ABI_CACHE.get_or_init(|| Cache::new(256))
+    }
+
+    fn key(node_url: &str, address: &AccountAddress, module: &str) -> String {
+        format!("{node_url}|{address}::{module}")
+    }
+
+    pub async fn get(
+        &self,
+        node_url: &str,
+        address: &AccountAddress,
+        module: &str,
+    ) -> Option<MoveModule> {
+        let key = Self::key(node_url, address, module);
+        self.cache().get(&key).await
+    }
+
+    pub async fn set(&self, node_url: &str, address: &AccountAddress, abi: MoveModule) {
+        let key = Self::key(node_url, address, abi.name.as_str());
+        self.cache().insert(key, abi).await;
+    }
+}
+
+/// Function argument encoded against its abi parameter type
+#[derive(Debug, Clone)]
+pub enum AbiArg {
+    Json(Value),
+    /// Plain string, vectors and structs other than `String`/`Object` are parsed as json
+    Str(String),
+}
+
+impl From<Value> for AbiArg {
+    fn from(value: Value) -> Self {
+        AbiArg::Json(value)
+    }
+}
+
+impl From<&str> for AbiArg {
+    fn from(s: &str) -> Self {
+        AbiArg::Str(s.to_string())
+    }
+}
+
+impl From<String> for AbiArg {
+    fn from(s: String) -> Self {
+        AbiArg::Str(s)
+    }
+}
+
+impl AbiArg {
+    fn into_value(self, ty: &MoveType) -> Value {
+        match self {
+            AbiArg::Json(value) => value,
+            AbiArg::Str(s) if is_scalar(ty) => Value::String(s),
+            AbiArg::Str(s) => match option_inner(ty) {
+                Some(_) if s.is_empty() || s == "null" => Value::Null,
+                Some(inner) if is_scalar(inner) => Value::String(s),
+                _ => serde_json::from_str(&s).unwrap_or(Value::String(s)),
+            },
+        }
+    }
+}
+
+/// Which kind of function an abi lookup expects
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AbiFnKind {
+    Entry,
+    View,
+}
+
+/// Exposed `kind` function `fun` of the module abi
+pub fn find_fn<'m>(abi: &'m MoveModule, fun: &str, kind: AbiFnKind) -> AppResult<&'m MoveFunction> {
+    let module = format!("{}::{}", abi.address, abi.name);
+    let func = abi
+        .exposed_functions
+        .iter()
+        .find(|func| func.name.as_str() == fun)
+        .ok_or_else(|| app_err!(&EdsErr::AbiFnNotFound, format!("{module}::{fun}")))?;
+
+    let matched = match kind {
+        AbiFnKind::Entry => func.is_entry,
+        AbiFnKind::View => func.is_view,
+    };
+    if !matched {
+        let msg = format!("{module}::{fun} is not a {kind:?} function");
+        return Err(app_err!(&EdsErr::AbiFnNotFound, msg));
+    }
+    Ok(func)
+}
+
+/// Encode `args` into bcs against the parameters of `func`, leading signers excluded
+pub fn encode_args(
+    func: &MoveFunction,
+    type_args: &[TypeTag],
+    args: Vec<AbiArg>,
+) -> AppResult<Vec<Vec<u8>>> {
+    let fun = func.name.as_str();
+    if type_args.len() != func.generic_type_params.len() {
+        let (expected, got) = (func.generic_type_params.len(), type_args.len());
+        let msg = format!("{fun} expects {expected} type args, got {got}");
+        return Err(app_err!(&EdsErr::AbiArgArity, msg));
+    }
+
+    let params: Vec<_> = func.params.iter().skip_while(|ty| is_signer(ty)).collect();
+    if args.len() != params.len() {
+        let (expected, got) = (params.len(), args.len());
+        let msg = format!("{fun} expects {expected} args, got {got}");
+        return Err(app_err!(&EdsErr::AbiArgArity, msg));
+    }
+
+    params
+        .into_iter()
+        .zip(args)
+        .enumerate()
+        .map(|(i, (ty, arg))| {
+            let encoded = substitute(ty, type_args).and_then(|ty| {
+                let value = arg.into_value(&ty);
+                let mut out = vec![];
+                encode_value(&ty, &value, &mut out).map(|_| out)
+            });
+            encoded.map_err(|msg| app_err!(&EdsErr::AbiArgType, format!("{fun} arg[{i}]: {msg}")))
+        })
+        .collect()
+}
+
+type EncodeResult<T> = Result<T, String>;
+
+/// Replace generic type params with the given type args
+fn substitute(ty: &MoveType, type_args: &[TypeTag]) -> EncodeResult<MoveType> {
+    Ok(match ty {
+        MoveType::GenericTypeParam { index } => type_args
+            .get(*index as usize)
+            .map(MoveType::from)
+            .ok_or_else(|| format!("missing type arg T{index}"))?,
+        MoveType::Vector { items } => MoveType::Vector {
+            items: Box::new(substitute(items, type_args)?),
+        },
+        MoveType::Struct(tag) => {
+            let generic_type_params = tag
+                .generic_type_params
+                .iter()
+                .map(|ty| substitute(ty, type_args))
+                .collect::<EncodeResult<_>>()?;
+            MoveType::Struct(MoveStructTag {
+                generic_type_params,
+                ..tag.clone()
+            })
+        }
+        ty => ty.clone(),
+    })
+}
+
+fn encode_value(ty: &MoveType, value: &Value, out: &mut Vec<u8>) -> EncodeResult<()> {
+    match ty {
+        MoveType::Bool => match value {
+            Value::Bool(b) => append(out, b),
+            Value::String(s) => append(out, &parse_str::<bool>(ty, s)?),
+            _ => Err(mismatch(ty, value)),
+        },
+        MoveType::U8 => append(out, &parse_uint::<u8>(ty, value)?),
+        MoveType::U16 => append(out, &parse_uint::<u16>(ty, value)?),
+        MoveType::U32 => append(out, &parse_uint::<u32>(ty, value)?),
+        MoveType::U64 => append(out, &parse_uint::<u64>(ty, value)?),
+        MoveType::U128 => append(out, &parse_uint::<u128>(ty, value)?),
+        MoveType::U256 => append(out, &parse_uint::<U256>(ty, value)?),
+        MoveType::Address => append(out, &parse_address(ty, value)?),
+        MoveType::Vector { items } => encode_vector(ty, items, value, out),
+        MoveType::Struct(tag) => encode_struct(ty, tag, value, out),
+        MoveType::Signer | MoveType::Reference { .. } => {
+            Err(format!("{ty} can not be passed as an argument"))
+        }
+        _ => Err(format!("unsupported parameter type {ty}")),
+    }
+}
+
+fn encode_vector(
+    ty: &MoveType,
+    items: &MoveType,
+    value: &Value,
+    out: &mut Vec<u8>,
+) -> EncodeResult<()> {
+    match (items, value) {
+        // vector<u8> as hex, with or without 0x
+        (MoveType::U8, Value::String(s)) => {
+            let bytes = hex::decode(s.trim_start_matches("0x"))
+                .map_err(|e| format!("expected hex string of {ty}, got {value}: {e}"))?;
+            append(out, &bytes)
+        }
+        (_, Value::Array(values)) => {
+            write_uleb128(out, values.len());
+            values
+                .iter()
+                .try_for_each(|value| encode_value(items, value, out))
+        }
+        _ => Err(mismatch(ty, value)),
+    }
+}
+
+fn encode_struct(
+    ty: &MoveType,
+    tag: &MoveStructTag,
+    value: &Value,
+    out: &mut Vec<u8>,
+) -> EncodeResult<()> {
+    if *tag.address.inner() != AccountAddress::ONE {
+        return Err(format!("unsupported struct type {ty}"));
+    }
+
+    match (tag.module.as_str(), tag.name.as_str()) {
+        ("string", "String") => match value {
+            Value::String(s) => append(out, s),
+            _ => Err(mismatch(ty, value)),
+        },
+        ("object", "Object") => append(out, &parse_address(ty, value)?),
+        ("option", "Option") => {
+            let inner = tag
+                .generic_type_params
+                .first()
+                .ok_or_else(|| format!("{ty} without type param"))?;
+            match value {
+                Value::Null => append(out, &0u8),
+                value => {
+                    append(out, &1u8)?;
+                    encode_value(inner, value, out)
+                }
+            }
+        }
+        _ => Err(format!("unsupported struct type {ty}")),
+    }
+}
+
+fn append<T: Serialize + ?Sized>(out: &mut Vec<u8>, value: &T) -> EncodeResult<()> {
+    let bytes = bcs::to_bytes(value).map_err(|e| e.to_string())?;
+    out.extend(bytes);
+    Ok(())
+}
+
+fn write_uleb128(out: &mut Vec<u8>, mut len: usize) {
+    while len >= 0x80 {
+        out.push((len as u8 & 0x7f) | 0x80);
+        len >>= 7;
+    }
+    out.push(len as u8);
+}
+
+/// Integers as json numbers or decimal strings, numbers above u64 must be strings
+fn parse_uint<T: FromStr>(ty: &MoveType, value: &Value) -> EncodeResult<T> {
+    match value {
+        Value::Number(n) if n.is_u64() => parse_str(ty, &n.to_string()),
+        Value::String(s) => parse_str(ty, s),
+        _ => Err(mismatch(ty, value)),
+    }
+}
+
+/// Addresses in bs58 or hex
+fn parse_address(ty: &MoveType, value: &Value) -> EncodeResult<AccountAddress> {
+    match value {
+        Value::String(s) => parse_str(ty, s),
+        _ => Err(mismatch(ty, value)),
+    }
+}
+
+fn parse_str<T: FromStr>(ty: &MoveType, s: &str) -> EncodeResult<T> {
+    s.parse::<T>()
+        .map_err(|_| format!("expected {ty}, got \"{s}\""))
+}
+
+fn mismatch(ty: &MoveType, value: &Value) -> String {
+    format!("expected {ty}, got {value}")
+}
+
+fn is_signer(ty: &MoveType) -> bool {
+    match ty {
+        MoveType::Signer => true,
+        MoveType::Reference { to, .. } => matches!(to.as_ref(), MoveType::Signer),
+        _ => false,
+    }
+}
+
+/// Types given as a single json string
+fn is_scalar(ty: &MoveType) -> bool {
+    match ty {
+        MoveType::Struct(tag) => {
+            matches!(
+                (tag.module.as_str(), tag.name.as_str()),
+                ("string", "String") | ("object", "Object")
+            )
+        }
+        MoveType::Vector { items } => matches!(items.as_ref(), MoveType::U8),
+        _ => true,
+    }
+}
+
+fn option_inner(ty: &MoveType) -> Option<&MoveType> {
+    match ty {
+        MoveType::Struct(tag)
+            if (tag.module.as_str(), tag.name.as_str()) == ("option", "Option") =>
+        {
+            tag.generic_type_params.first()
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn encode(ty: &str, arg: impl Into<AbiArg>) -> EncodeResult<Vec<u8>> {
+        let ty = MoveType::from_str(ty).unwrap();
+        let value = arg.into().into_value(&ty);
+        let mut out = vec![];
+        encode_value(&ty, &value, &mut out).map(|_| out)
+    }
+
+    #[test]
+    fn integers_match_declared_width() {
+        assert_eq!(encode("u64", json!(7)).unwrap(), 7u64.to_le_bytes());
+        assert_eq!(encode("u128", "7").unwrap(), 7u128.to_le_bytes());
+        assert!(encode("u8", json!(256)).is_err());
+        assert!(encode("u64", json!(-1)).is_err());
+        assert!(encode("u64", json!("abc")).is_err());
+    }
+
+    #[test]
+    fn vectors_and_strings() {
+        assert_eq!(encode("vector<u8>", "0x0102").unwrap(), vec![2, 1, 2]);
+        assert_eq!(
+            encode("vector<u16>", "[1, 2]").unwrap(),
+            vec![2, 1, 0, 2, 0]
+        );
+        assert_eq!(
+            encode("0x1::string::String", "ab").unwrap(),
+            vec![2, b'a', b'b']
+        );
+        assert!(encode("vector<u64>", json!(1)).is_err());
+    }
+
+    #[test]
+    fn options_and_addresses() {
+        assert_eq!(encode("0x1::option::Option<u8>", "").unwrap(), vec![0]);
+        assert_eq!(encode("0x1::option::Option<u8>", "5").unwrap(), vec![1, 5]);
+
+        let addr = encode("address", "0x1").unwrap();
+        assert_eq!(addr, AccountAddress::ONE.to_vec());
+        assert_eq!(
+            encode("0x1::object::Object<0x1::fungible_asset::Metadata>", "0x1").unwrap(),
+            addr
+        );
+    }
+
+    #[test]
+    fn uleb128_lengths() {
+        let mut out = vec![];
+        write_uleb128(&mut out, 300);
+        assert_eq!(out, vec![0xac, 0x02]);
+    }
+}
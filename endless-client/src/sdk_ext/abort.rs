@@ -0,0 +1,359 @@
+use crate::error::EdsErr;
+use base_infra::result::AppResult;
+use base_infra::{app_err, map_err};
+use endless_sdk::move_types::account_address::AccountAddress;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Arc, OnceLock};
+
+/// Error maps of the `0x1` framework modules, by `address::module` and reason.
+/// Generated from the framework sources by `examples/framework_error_maps_example.rs`
+const FRAMEWORK_ERROR_MAPS: &str = include_str!("error_maps/framework.json");
+
+static FRAMEWORK: OnceLock<Arc<ErrorMaps>> = OnceLock::new();
+
+/// `std::error` category of a canonical abort code
+pub fn category_name(category: u8) -> Option<&'static str> {
+    let name = match category {
+        0x1 => "INVALID_ARGUMENT",
+        0x2 => "OUT_OF_RANGE",
+        0x3 => "INVALID_STATE",
+        0x4 => "UNAUTHENTICATED",
+        0x5 => "PERMISSION_DENIED",
+        0x6 => "NOT_FOUND",
+        0x7 => "ABORTED",
+        0x8 => "ALREADY_EXISTS",
+        0x9 => "RESOURCE_EXHAUSTED",
+        0xA => "CANCELLED",
+        0xB => "INTERNAL",
+        0xC => "NOT_IMPLEMENTED",
+        0xD => "UNAVAILABLE",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// A reason constant of a module, `EINSUFFICIENT_BALANCE`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorDesc {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+/// Move abort split into category and reason, named by the error map of the aborting module
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedAbort {
+    /// Aborting module as reported, `0x1::coin`, or `script`
+    pub location: String,
+    pub code: u64,
+    /// Third byte of the code, see `category_name`
+    pub category: u8,
+    /// Two lowest bytes of the code, the constant the module aborted with
+    pub reason: u64,
+    pub desc: Option<ErrorDesc>,
+}
+
+impl DecodedAbort {
+    /// Split `code` without naming the reason
+    pub fn new(location: &str, code: u64) -> Self {
+        Self {
+            location: location.to_string(),
+            code,
+            category: ((code >> 16) & 0xff) as u8,
+            reason: code & 0xffff,
+            desc: None,
+        }
+    }
+
+    pub fn category_name(&self) -> Option<&'static str> {
+        category_name(self.category)
+    }
+
+    /// `EINSUFFICIENT_BALANCE`, when the error map of the module has the reason
+    pub fn reason_name(&self) -> Option<&str> {
+        self.desc.as_ref().map(|desc| desc.name.as_str())
+    }
+}
+
+impl Display for DecodedAbort {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (location, code) = (&self.location, self.code);
+        match &self.desc {
+            Some(desc) if desc.description.is_empty() => {
+                write!(f, "{location}::{}({code:#x})", desc.name)
+            }
+            Some(desc) => write!(
+                f,
+                "{location}::{}({code:#x}): {}",
+                desc.name, desc.description
+            ),
+            None => match self.category_name() {
+                Some(category) => write!(f, "{location}: {category}({code:#x})"),
+                None => write!(f, "{location}: {code:#x}"),
+            },
+        }
+    }
+}
+
+/// Reason names of move modules, the bundled `0x1` framework ones plus any loaded
+#[derive(Debug, Clone, Default)]
+pub struct ErrorMaps {
+    modules: HashMap<(AccountAddress, String), HashMap<u64, ErrorDesc>>,
+}
+
+impl ErrorMaps {
+    /// Error maps of the `0x1` framework modules bundled with this crate, parsed once.
+    /// Clone them to add maps of other modules
+    pub fn framework() -> &'static Self {
+        Self::shared_framework_ref()
+    }
+
+    /// The bundled framework maps without copying them, for clients that default to them
+    pub(crate) fn shared_framework() -> Arc<Self> {
+        Self::shared_framework_ref().clone()
+    }
+
+    fn shared_framework_ref() -> &'static Arc<Self> {
+        FRAMEWORK.get_or_init(|| {
+            let maps = Self::default().with_json(FRAMEWORK_ERROR_MAPS);
+            Arc::new(maps.expect("bundled framework error maps"))
+        })
+    }
+
+    /// Add the error maps of a json file, replacing reasons already known
+    pub fn with_file(self, path: impl AsRef<Path>) -> AppResult<Self> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path)
+            .map_err(map_err!(&EdsErr::ReadErrorMap, path.display().to_string()))?;
+        self.with_json(&json)
+    }
+
+    /// Add error maps as json, by `address::module` and then by reason:
+    ///
+    /// ```json
+    /// { "0x1::coin": { "6": { "name": "EINSUFFICIENT_BALANCE", "description": "..." } } }
+    /// ```
+    pub fn with_json(mut self, json: &str) -> AppResult<Self> {
+        let maps: HashMap<String, HashMap<String, ErrorDesc>> =
+            serde_json::from_str(json).map_err(map_err!(&EdsErr::ParseErrorMap))?;
+
+        for (module, reasons) in maps {
+            let key = module_key(&module)
+                .ok_or_else(|| app_err!(&EdsErr::ParseErrorMap, format!("module[{module}]")))?;
+            let entry = self.modules.entry(key).or_default();
+            for (reason, desc) in reasons {
+                let reason = parse_reason(&reason).ok_or_else(|| {
+                    app_err!(&EdsErr::ParseErrorMap, format!("{module} reason[{reason}]"))
+                })?;
+                entry.insert(reason, desc);
+            }
+        }
+        Ok(self)
+    }
+
+    /// Add the error maps of the `.move` files under `dir`, replacing reasons already known
+    pub fn with_move_sources(mut self, dir: impl AsRef<Path>) -> AppResult<Self> {
+        let dir = dir.as_ref();
+        let entries = std::fs::read_dir(dir).and_then(|entries| {
+            let paths = entries.map(|entry| entry.map(|entry| entry.path()));
+            paths.collect::<Result<Vec<_>, _>>()
+        });
+        let mut entries =
+            entries.map_err(map_err!(&EdsErr::ReadErrorMap, dir.display().to_string()))?;
+        entries.sort();
+
+        for path in entries {
+            if path.is_dir() {
+                self = self.with_move_sources(&path)?;
+            } else if path.extension().is_some_and(|ext| ext == "move") {
+                let source = std::fs::read_to_string(&path)
+                    .map_err(map_err!(&EdsErr::ReadErrorMap, path.display().to_string()))?;
+                self = self.with_move_source(&source);
+            }
+        }
+        Ok(self)
+    }
+
+    /// Add the `const E...: u64` reasons of the modules in a move source, named by the
+    /// constant and described by its doc comment. Modules at a named address other than
+    /// the `0x1` framework ones and `#[test_only]` modules are skipped
+    pub fn with_move_source(mut self, source: &str) -> Self {
+        let (mut module, mut test_only, mut doc) = (None, false, Vec::new());
+        for line in source.lines().map(str::trim) {
+            if let Some(comment) = line.strip_prefix("///") {
+                doc.push(comment.trim());
+                continue;
+            }
+            if line.starts_with("#[") {
+                test_only |= line == "#[test_only]";
+                continue;
+            }
+            if let Some(decl) = line.strip_prefix("module ") {
+                module = if test_only {
+                    None
+                } else {
+                    move_module_key(decl)
+                };
+            } else if let (Some(key), Some((name, reason)), false) =
+                (&module, move_reason(line), test_only)
+            {
+                let desc = ErrorDesc {
+                    name: name.to_string(),
+                    description: doc.join(" "),
+                };
+                let entry = self.modules.entry(key.clone()).or_default();
+                entry.insert(reason, desc);
+            }
+            test_only = false;
+            doc.clear();
+        }
+        self
+    }
+
+    /// Json of `with_json`, modules and reasons in order
+    pub fn to_json(&self) -> AppResult<String> {
+        let maps: BTreeMap<String, BTreeMap<u64, &ErrorDesc>> = self
+            .modules
+            .iter()
+            .map(|((address, name), reasons)| {
+                let module = format!("{}::{name}", address.to_hex_literal());
+                (module, reasons.iter().map(|(r, desc)| (*r, desc)).collect())
+            })
+            .collect();
+        serde_json::to_string_pretty(&maps).map_err(map_err!(&EdsErr::ParseErrorMap))
+    }
+
+    /// Reason constant of `module` (`0x1::coin`)
+    pub fn get(&self, module: &str, reason: u64) -> Option<&ErrorDesc> {
+        let key = module_key(module)?;
+        self.modules.get(&key)?.get(&reason)
+    }
+
+    /// Split `code` of an abort in `location` and name its reason
+    pub fn decode(&self, location: &str, code: u64) -> DecodedAbort {
+        let mut abort = DecodedAbort::new(location, code);
+        abort.desc = self.get(location, abort.reason).cloned();
+        abort
+    }
+}
+
+/// `0x1::coin` or `<bs58 address>::coin`
+fn module_key(module: &str) -> Option<(AccountAddress, String)> {
+    let (address, name) = module.split_once("::")?;
+    let address = AccountAddress::from_str(address).ok()?;
+    Some((address, name.to_string()))
+}
+
+/// `endless_framework::coin {` of a move module declaration
+fn move_module_key(decl: &str) -> Option<(AccountAddress, String)> {
+    let (address, rest) = decl.split_once("::")?;
+    let name = rest.split(|c: char| c == '{' || c.is_whitespace()).next()?;
+    let address = match address.trim() {
+        "std" | "endless_std" | "endless_framework" => AccountAddress::ONE,
+        address => AccountAddress::from_str(address).ok()?,
+    };
+    Some((address, name.to_string()))
+}
+
+/// `const EINSUFFICIENT_BALANCE: u64 = 6;`
+fn move_reason(line: &str) -> Option<(&str, u64)> {
+    let decl = line.strip_prefix("const ")?;
+    let (name, rest) = decl.split_once(':')?;
+    let (ty, value) = rest.split_once('=')?;
+    let name = name.trim();
+    if !name.starts_with('E') || ty.trim() != "u64" {
+        return None;
+    }
+    let value = value.trim().trim_end_matches(';').trim();
+    Some((name, parse_reason(value)?))
+}
+
+/// Decimal or `0x` hex
+fn parse_reason(reason: &str) -> Option<u64> {
+    match reason.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => reason.parse().ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_category_and_reason() {
+        let abort = DecodedAbort::new("0x1::coin", 0x10006);
+        assert_eq!((abort.category, abort.reason), (1, 6));
+        assert_eq!(abort.category_name(), Some("INVALID_ARGUMENT"));
+        assert_eq!(abort.to_string(), "0x1::coin: INVALID_ARGUMENT(0x10006)");
+    }
+
+    #[test]
+    fn names_framework_and_user_reasons() {
+        let maps = ErrorMaps::framework();
+        let abort = maps.decode("0x1::fungible_asset", 0x10004);
+        assert_eq!(abort.reason_name(), Some("EINSUFFICIENT_BALANCE"));
+        assert_eq!(
+            maps.decode("0x1::coin", 0x10006).reason_name(),
+            Some("EINSUFFICIENT_BALANCE")
+        );
+        assert_eq!(maps.decode("script", 0x10006).reason_name(), None);
+
+        let user = r#"{ "0x1::locking_coin_ex": { "0x2": { "name": "ENOT_UNLOCKED" } } }"#;
+        let maps = maps.clone().with_json(user).unwrap();
+        let abort = maps.decode("0x1::locking_coin_ex", 0x30002);
+        assert_eq!(
+            abort.to_string(),
+            "0x1::locking_coin_ex::ENOT_UNLOCKED(0x30002)"
+        );
+        assert!(maps.get("0x1::coin", 6).is_some());
+
+        assert!(ErrorMaps::default().with_json(r#"{ "coin": {} }"#).is_err());
+    }
+
+    #[test]
+    fn reads_reasons_from_move_source() {
+        let source = r#"
+            /// Locked coins of an account
+            module endless_framework::locking_coin_ex {
+                /// No locked coins to claim yet.
+                /// Wait for the unlock epoch
+                const ENOT_UNLOCKED: u64 = 2;
+                const EMAX: u64 = 0x10;
+                const MAX_EPOCHS: u64 = 100;
+            }
+
+            #[test_only]
+            module endless_framework::locking_coin_ex_tests {
+                const ETEST: u64 = 1;
+            }
+        "#;
+        let maps = ErrorMaps::default().with_move_source(source);
+        let desc = maps.get("0x1::locking_coin_ex", 2).unwrap();
+        assert_eq!(desc.name, "ENOT_UNLOCKED");
+        assert_eq!(
+            desc.description,
+            "No locked coins to claim yet. Wait for the unlock epoch"
+        );
+        assert_eq!(maps.get("0x1::locking_coin_ex", 0x10).unwrap().name, "EMAX");
+        assert!(maps.get("0x1::locking_coin_ex", 100).is_none());
+        assert!(maps.get("0x1::locking_coin_ex_tests", 1).is_none());
+
+        let json = maps.to_json().unwrap();
+        let parsed = ErrorMaps::default().with_json(&json).unwrap();
+        assert_eq!(parsed.get("0x1::locking_coin_ex", 2), Some(desc));
+    }
+
+    #[test]
+    fn framework_maps_parsed_once() {
+        assert!(std::ptr::eq(ErrorMaps::framework(), ErrorMaps::framework()));
+        assert!(Arc::ptr_eq(
+            &ErrorMaps::shared_framework(),
+            &ErrorMaps::shared_framework()
+        ));
+    }
+}
@@ -0,0 +1,258 @@
+use crate::error::EdsErr;
+use base_infra::map_err;
+use base_infra::result::AppResult;
+use endless_sdk::move_types::account_address::AccountAddress;
+use endless_sdk::rest_client::Transaction;
+use endless_sdk::rest_client::endless_api_types::{Event, MoveType, UserTransaction};
+use serde::de::{DeserializeOwned, Deserializer};
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Rust type of a move event
+pub trait MoveEvent: DeserializeOwned + Send + Sync + 'static {
+    /// Move type string, such as `0x1::fungible_asset::Deposit`
+    const TYPE: &'static str;
+}
+
+/// `0x1::coin::WithdrawEvent`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoinWithdraw {
+    #[serde(deserialize_with = "from_str_or_num")]
+    pub amount: u128,
+}
+
+impl MoveEvent for CoinWithdraw {
+    const TYPE: &'static str = "0x1::coin::WithdrawEvent";
+}
+
+/// `0x1::coin::DepositEvent`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoinDeposit {
+    #[serde(deserialize_with = "from_str_or_num")]
+    pub amount: u128,
+}
+
+impl MoveEvent for CoinDeposit {
+    const TYPE: &'static str = "0x1::coin::DepositEvent";
+}
+
+/// `0x1::fungible_asset::Withdraw`, `store` is the fungible store withdrawn from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FaWithdraw {
+    pub store: AccountAddress,
+    #[serde(deserialize_with = "from_str_or_num")]
+    pub amount: u128,
+}
+
+impl MoveEvent for FaWithdraw {
+    const TYPE: &'static str = "0x1::fungible_asset::Withdraw";
+}
+
+/// `0x1::fungible_asset::Deposit`, `store` is the fungible store deposited to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FaDeposit {
+    pub store: AccountAddress,
+    #[serde(deserialize_with = "from_str_or_num")]
+    pub amount: u128,
+}
+
+impl MoveEvent for FaDeposit {
+    const TYPE: &'static str = "0x1::fungible_asset::Deposit";
+}
+
+/// Integers above 53 bits are json strings in rest responses
+pub fn from_str_or_num<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StrOrNum {
+        Str(String),
+        Num(serde_json::Number),
+    }
+
+    let s = match StrOrNum::deserialize(deserializer)? {
+        StrOrNum::Str(s) => s,
+        StrOrNum::Num(n) => n.to_string(),
+    };
+    s.parse().map_err(serde::de::Error::custom)
+}
+
+/// Event decoded into the rust type registered for its move type
+pub struct DecodedEvent {
+    pub type_str: String,
+    value: Box<dyn Any + Send + Sync>,
+}
+
+impl DecodedEvent {
+    pub fn is<E: MoveEvent>(&self) -> bool {
+        self.value.is::<E>()
+    }
+
+    pub fn downcast_ref<E: MoveEvent>(&self) -> Option<&E> {
+        self.value.downcast_ref()
+    }
+}
+
+type DecodeFn = fn(&serde_json::Value) -> serde_json::Result<Box<dyn Any + Send + Sync>>;
+
+/// Move event type string -> rust type
+#[derive(Clone, Default)]
+pub struct EventRegistry {
+    decoders: HashMap<String, DecodeFn>,
+}
+
+impl EventRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registry of the coin and fungible asset withdraw/deposit events
+    pub fn with_builtins() -> Self {
+        Self::new()
+            .with::<CoinWithdraw>()
+            .with::<CoinDeposit>()
+            .with::<FaWithdraw>()
+            .with::<FaDeposit>()
+    }
+
+    pub fn with<E: MoveEvent>(mut self) -> Self {
+        self.register::<E>();
+        self
+    }
+
+    pub fn register<E: MoveEvent>(&mut self) {
+        let decode: DecodeFn = |data| Ok(Box::new(serde_json::from_value::<E>(data.clone())?));
+        self.decoders.insert(normalize(E::TYPE), decode);
+    }
+
+    pub fn is_registered(&self, type_str: &str) -> bool {
+        self.decoders.contains_key(&normalize(type_str))
+    }
+
+    /// None when no rust type is registered for the event type
+    pub fn decode(&self, event: &Event) -> Option<AppResult<DecodedEvent>> {
+        self.decode_data(&event.typ.to_string(), &event.data)
+    }
+
+    /// Decode every registered event, skipping unregistered ones
+    pub fn decode_all(&self, events: &[Event]) -> AppResult<Vec<DecodedEvent>> {
+        events
+            .iter()
+            .filter_map(|event| self.decode(event))
+            .collect()
+    }
+
+    fn decode_data(
+        &self,
+        type_str: &str,
+        data: &serde_json::Value,
+    ) -> Option<AppResult<DecodedEvent>> {
+        let decode = self.decoders.get(type_str)?;
+        let decoded = decode(data).map(|value| DecodedEvent {
+            type_str: type_str.to_string(),
+            value,
+        });
+        Some(decoded.map_err(map_err!(&EdsErr::DecodeEvent, type_str.to_string())))
+    }
+}
+
+/// Type strings as the rest api prints them, so registered and received types compare equal
+fn normalize(type_str: &str) -> String {
+    match MoveType::from_str(type_str) {
+        Ok(ty) => ty.to_string(),
+        Err(_) => type_str.to_string(),
+    }
+}
+
+/// Events of `E` among `events`, in emission order
+pub fn events_of<'e, E: MoveEvent>(
+    events: impl IntoIterator<Item = &'e Event>,
+) -> AppResult<Vec<E>> {
+    let type_str = normalize(E::TYPE);
+    events
+        .into_iter()
+        .filter(|event| event.typ.to_string() == type_str)
+        .map(|event| {
+            serde_json::from_value(event.data.clone())
+                .map_err(map_err!(&EdsErr::DecodeEvent, type_str.clone()))
+        })
+        .collect()
+}
+
+/// Events of committed or simulated transactions
+pub trait TxnEvents {
+    fn events(&self) -> Vec<&Event>;
+
+    fn events_of<E: MoveEvent>(&self) -> AppResult<Vec<E>> {
+        events_of(self.events())
+    }
+}
+
+impl TxnEvents for Transaction {
+    fn events(&self) -> Vec<&Event> {
+        match self {
+            Transaction::UserTransaction(txn) => txn.events.iter().collect(),
+            Transaction::BlockMetadataTransaction(txn) => txn.events.iter().collect(),
+            Transaction::GenesisTransaction(txn) => txn.events.iter().collect(),
+            _ => vec![],
+        }
+    }
+}
+
+impl TxnEvents for UserTransaction {
+    fn events(&self) -> Vec<&Event> {
+        self.events.iter().collect()
+    }
+}
+
+/// Simulation result
+impl TxnEvents for [UserTransaction] {
+    fn events(&self) -> Vec<&Event> {
+        self.iter().flat_map(|txn| txn.events.iter()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn decodes_registered_types() {
+        let registry = EventRegistry::with_builtins();
+        let type_str = normalize(CoinDeposit::TYPE);
+
+        let data = json!({ "amount": "340282366920938463463374607431768211455" });
+        let event = registry.decode_data(&type_str, &data).unwrap().unwrap();
+        assert!(event.is::<CoinDeposit>());
+        assert_eq!(
+            event.downcast_ref::<CoinDeposit>().unwrap().amount,
+            u128::MAX
+        );
+
+        let data = json!({ "amount": 5 });
+        let event = registry.decode_data(&type_str, &data).unwrap().unwrap();
+        assert_eq!(event.downcast_ref::<CoinDeposit>().unwrap().amount, 5);
+    }
+
+    #[test]
+    fn unregistered_and_malformed() {
+        let registry = EventRegistry::with_builtins();
+        assert!(
+            registry
+                .decode_data("0x1::m::Unknown", &json!({}))
+                .is_none()
+        );
+
+        let type_str = normalize(CoinWithdraw::TYPE);
+        let data = json!({ "amount": "abc" });
+        assert!(registry.decode_data(&type_str, &data).unwrap().is_err());
+    }
+}
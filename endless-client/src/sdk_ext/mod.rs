@@ -1,3 +1,8 @@
+pub mod abi;
+pub mod abort;
 pub mod account;
+pub mod events;
+pub mod offline;
 pub mod rest_client;
+pub mod retry;
 pub mod types;
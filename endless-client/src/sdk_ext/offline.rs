@@ -0,0 +1,166 @@
+use crate::error::EdsErr;
+use crate::sdk_ext::types::EntryFnArgs;
+use crate::utils::bcs_ext::BcsExt;
+use base_infra::result::AppResult;
+use base_infra::{app_err, map_err};
+use endless_sdk::helper_client::Overrides;
+use endless_sdk::move_types::account_address::AccountAddress;
+use endless_sdk::transaction_builder::TransactionBuilder;
+use endless_sdk::types::LocalAccount;
+use endless_sdk::types::chain_id::ChainId;
+use endless_sdk::types::transaction::{RawTransaction, SignedTransaction, TransactionPayload};
+
+/// What the online machine fixes for a transaction signed elsewhere,
+/// nothing is fetched from the node when building
+#[derive(Debug, Clone, Copy)]
+pub struct OfflineTxnParams {
+    pub chain_id: ChainId,
+    pub sequence_number: u64,
+    /// Unix seconds after which the transaction is rejected
+    pub expiration_timestamp_secs: u64,
+}
+
+/// Raw transaction built on an online machine, to be signed on an offline one
+#[derive(Debug, Clone)]
+pub struct UnsignedTxn(RawTransaction);
+
+impl UnsignedTxn {
+    /// Gas comes from `overrides`, `timeout_secs` is ignored for the explicit expiry
+    pub fn new(
+        sender: AccountAddress,
+        payload: TransactionPayload,
+        params: OfflineTxnParams,
+        overrides: Option<Overrides>,
+    ) -> Self {
+        let overrides = overrides.unwrap_or_default();
+        let expires_at = params.expiration_timestamp_secs;
+        let raw_txn = TransactionBuilder::new(payload, expires_at, params.chain_id)
+            .max_gas_amount(overrides.max_gas_amount)
+            .gas_unit_price(overrides.gas_unit_price)
+            .sender(sender)
+            .sequence_number(params.sequence_number)
+            .build();
+        Self(raw_txn)
+    }
+
+    /// Sender, payload and gas of `args`, secondary signers and fee payer are not supported
+    pub fn from_entry_fn(args: &EntryFnArgs<'_>, params: OfflineTxnParams) -> Self {
+        Self::new(
+            args.signer.address(),
+            args.payload(),
+            params,
+            args.gas_overrides(),
+        )
+    }
+
+    pub fn raw_txn(&self) -> &RawTransaction {
+        &self.0
+    }
+
+    pub fn to_bcs(&self) -> AppResult<Vec<u8>> {
+        self.0.to_bytes()
+    }
+
+    pub fn to_hex(&self) -> AppResult<String> {
+        Ok(hex::encode(self.to_bcs()?))
+    }
+
+    pub fn from_bcs(bytes: &[u8]) -> AppResult<Self> {
+        let raw_txn = bcs::from_bytes(bytes).map_err(map_err!(&EdsErr::DecodeRawTxn))?;
+        Ok(Self(raw_txn))
+    }
+
+    /// With or without `0x`
+    pub fn from_hex(s: &str) -> AppResult<Self> {
+        let bytes = hex::decode(s.trim_start_matches("0x"))
+            .map_err(map_err!(&EdsErr::DecodeRawTxn, "invalid hex"))?;
+        Self::from_bcs(&bytes)
+    }
+
+    /// Sign as the sender, the sequence number of `signer` is left untouched
+    pub fn sign(self, signer: &LocalAccount) -> AppResult<SignedTxn> {
+        let sender = self.0.sender();
+        if sender != signer.address() {
+            let msg = format!("sender[{sender}], signer[{}]", signer.address());
+            return Err(app_err!(&EdsErr::OfflineSignerMismatch, msg));
+        }
+        Ok(SignedTxn(signer.sign_transaction(self.0)))
+    }
+}
+
+/// Signed transaction carried back to an online machine for submission
+#[derive(Debug, Clone)]
+pub struct SignedTxn(SignedTransaction);
+
+impl SignedTxn {
+    pub fn signed_txn(&self) -> &SignedTransaction {
+        &self.0
+    }
+
+    pub fn into_inner(self) -> SignedTransaction {
+        self.0
+    }
+
+    pub fn to_bcs(&self) -> AppResult<Vec<u8>> {
+        self.0.to_bytes()
+    }
+
+    pub fn to_hex(&self) -> AppResult<String> {
+        Ok(hex::encode(self.to_bcs()?))
+    }
+
+    pub fn from_bcs(bytes: &[u8]) -> AppResult<Self> {
+        let signed_txn = bcs::from_bytes(bytes).map_err(map_err!(&EdsErr::DecodeSignedTxn))?;
+        Ok(Self(signed_txn))
+    }
+
+    /// With or without `0x`
+    pub fn from_hex(s: &str) -> AppResult<Self> {
+        let bytes = hex::decode(s.trim_start_matches("0x"))
+            .map_err(map_err!(&EdsErr::DecodeSignedTxn, "invalid hex"))?;
+        Self::from_bcs(&bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_node::test_account;
+
+    fn params() -> OfflineTxnParams {
+        OfflineTxnParams {
+            chain_id: ChainId::new(4),
+            sequence_number: 7,
+            expiration_timestamp_secs: 1_700_000_000,
+        }
+    }
+
+    #[test]
+    fn build_export_and_sign() {
+        let signer = test_account(0x11);
+        let args = vec![
+            AccountAddress::ONE.to_bytes().unwrap(),
+            5u128.to_bytes().unwrap(),
+        ];
+        let (mn, fun) = ("endless_account", "transfer");
+        let fn_args =
+            EntryFnArgs::new(&signer, AccountAddress::ONE, mn, fun, args, vec![]).unwrap();
+
+        let unsigned = UnsignedTxn::from_entry_fn(&fn_args, params());
+        let imported = UnsignedTxn::from_hex(&unsigned.to_hex().unwrap()).unwrap();
+        assert_eq!(imported.raw_txn(), unsigned.raw_txn());
+        assert_eq!(imported.raw_txn().sequence_number(), 7);
+
+        let signed = imported.sign(&signer).unwrap();
+        let blob = signed.to_bcs().unwrap();
+        assert_eq!(
+            SignedTxn::from_bcs(&blob).unwrap().signed_txn(),
+            signed.signed_txn()
+        );
+        assert_eq!(signer.sequence_number(), 0);
+
+        let other = test_account(0x22);
+        assert!(unsigned.sign(&other).is_err());
+        assert!(UnsignedTxn::from_bcs(&blob[..4]).is_err());
+    }
+}
@@ -1,75 +1,218 @@
 use crate::error::EdsErr;
-use crate::sdk_ext::types::{ChainIdCache, EntryFnArgs, ViewFnArgs};
+use crate::sdk_ext::offline::SignedTxn;
+use crate::sdk_ext::retry::RetryPolicy;
+use crate::sdk_ext::types::{ChainIdCache, EntryFnArgs, GasPriceCache, GasPrices, ViewFnArgs};
 use base_infra::map_err;
 use base_infra::result::AppResult;
-use endless_sdk::rest_client::endless_api_types::{IndexResponse, UserTransaction};
+use endless_sdk::helper_client::Overrides;
+use endless_sdk::rest_client::endless_api_types::{
+    HashValue, IndexResponse, Transaction, UserTransaction,
+};
+use endless_sdk::rest_client::error::RestError;
 use endless_sdk::rest_client::{Client, EndlessResult, PendingTransaction, Response};
 use endless_sdk::transaction_builder::TransactionBuilder;
+use endless_sdk::types::LocalAccount;
 use endless_sdk::types::chain_id::ChainId;
-use endless_sdk::types::transaction::TransactionPayload;
+use endless_sdk::types::transaction::{SignedTransaction, TransactionPayload};
 use serde::de::DeserializeOwned;
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 #[derive(Clone, Debug)]
 pub struct RestClient<'a> {
     client: &'a Client,
+    retry: RetryPolicy,
+    chain_id: ChainIdCache,
 }
 
 impl<'a> RestClient<'a> {
     pub fn new(client: &'a Client) -> Self {
-        Self { client }
+        Self {
+            client,
+            retry: RetryPolicy::none(),
+            chain_id: ChainIdCache::default(),
+        }
+    }
+
+    pub fn with_retry_policy(self, retry: RetryPolicy) -> Self {
+        Self { retry, ..self }
+    }
+
+    pub(crate) fn with_chain_id_cache(self, chain_id: ChainIdCache) -> Self {
+        Self { chain_id, ..self }
+    }
+
+    pub fn node_url(&self) -> String {
+        self.client.path_prefix_string()
     }
 
+    /// Chain id of the node, cached and checked against the node again once in a while
     pub async fn get_chain_id(&self) -> AppResult<ChainId> {
-        if let Some(chain_id) = ChainIdCache.get().await {
+        if let Some(chain_id) = self.chain_id.get() {
             return Ok(chain_id);
         }
+        self.verify_chain_id().await
+    }
 
+    /// Fetch the chain id from the node and check it against the cached one
+    pub async fn verify_chain_id(&self) -> AppResult<ChainId> {
         let chain_id = self.get_index().await?.inner().chain_id;
-        let chain_id = ChainId::new(chain_id);
-        ChainIdCache.set(chain_id).await;
-        Ok(chain_id)
+        self.check_chain_id(chain_id)
+    }
+
+    pub(crate) fn check_chain_id(&self, chain_id: u8) -> AppResult<ChainId> {
+        self.chain_id
+            .check(&self.node_url(), ChainId::new(chain_id))
+    }
+
+    /// Forget the cached chain id, e.g. after the node was redeployed with a new chain
+    pub fn invalidate_chain_id(&self) {
+        self.chain_id.invalidate();
     }
+
+    /// Gas unit prices estimated by the node, reused for a few seconds per node
+    pub async fn estimate_gas_price(&self) -> AppResult<GasPrices> {
+        let node_url = self.node_url();
+        if let Some(prices) = GasPriceCache.get(&node_url).await {
+            return Ok(prices);
+        }
+
+        let prices: GasPrices = self
+            .retry
+            .run("estimate_gas_price", || self.client.estimate_gas_price())
+            .await
+            .map_err(map_err!(&EdsErr::EstimateGasPrice))?
+            .into_inner()
+            .into();
+        GasPriceCache.set(&node_url, prices).await;
+        Ok(prices)
+    }
+
     pub async fn simulate_fun(
         &self,
         args: EntryFnArgs<'a>,
     ) -> AppResult<Response<Vec<UserTransaction>>> {
-        let chain_id = self.get_chain_id().await?;
-        let overrides = args.overrides.unwrap_or_default();
-
-        let expires_at = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map_err(map_err!(&EdsErr::SystemTimeErr))?
-            .as_secs()
-            + overrides.timeout_secs;
+        self.simulate_fun_with_estimation(args, true, false).await
+    }
 
-        let payload = TransactionPayload::EntryFunction(args.entry_fn);
-        let txn_builder = TransactionBuilder::new(payload, expires_at, chain_id)
-            .sender(args.signer.address())
-            .sequence_number(args.signer.sequence_number())
-            .max_gas_amount(overrides.max_gas_amount)
-            .gas_unit_price(overrides.gas_unit_price);
+    pub async fn simulate_fun_with_estimation(
+        &self,
+        args: EntryFnArgs<'a>,
+        estimate_max_gas_amount: bool,
+        estimate_gas_unit_price: bool,
+    ) -> AppResult<Response<Vec<UserTransaction>>> {
+        let signers = TxnSigners::new(&args);
+        let payload = args.payload();
+        let txn_builder = self.txn_builder(payload, args.gas_overrides()).await?;
 
-        let signed_txn = args.signer.sign_with_transaction_builder(txn_builder);
+        let signed_txn = signers.sign(txn_builder)?;
         let res = self
-            .client
-            .simulate_with_gas_estimation(&signed_txn, true, false)
-            .await
-            .map_err(map_err!(&EdsErr::SimulateTxnErr))?;
+            .retry
+            .run("simulate", || {
+                self.client.simulate_with_gas_estimation(
+                    &signed_txn,
+                    estimate_max_gas_amount,
+                    estimate_gas_unit_price,
+                )
+            })
+            .await;
 
-        // decrement sequence number
-        args.signer.decrement_sequence_number();
+        // decrement sequence number, also when the simulation failed
+        if signers.sequence_number.is_none() {
+            signers.signer.decrement_sequence_number();
+        }
 
-        Ok(res)
+        res.map_err(map_err!(&EdsErr::SimulateTxnErr))
     }
 
     pub async fn entry_fun(
         &self,
         args: EntryFnArgs<'a>,
     ) -> AppResult<Response<PendingTransaction>> {
-        let fn_name = args.fn_name;
+        let fn_name = args.fn_name.clone();
+        let signed_txn = self.sign_entry_fun(args).await?;
+        self.submit_txn(&signed_txn).await.map_err(map_err!(
+            &EdsErr::SubmitTxnErr,
+            format!("function[{fn_name}]")
+        ))
+    }
+
+    /// Sign with `args.sequence_number` if set, otherwise with the signer's own sequence number.
+    /// Secondary signers and the fee payer, if any, sign as well.
+    pub async fn sign_entry_fun(&self, args: EntryFnArgs<'a>) -> AppResult<SignedTransaction> {
+        let signers = TxnSigners::new(&args);
+        let payload = args.payload();
+        let txn_builder = self.txn_builder(payload, args.gas_overrides()).await?;
+        signers.sign(txn_builder)
+    }
+
+    pub async fn submit_txn(
+        &self,
+        signed_txn: &SignedTransaction,
+    ) -> EndlessResult<Response<PendingTransaction>> {
+        // resubmitting the same signed transaction is idempotent
+        let attempts = AtomicU32::new(0);
+        let res = self
+            .retry
+            .run("submit", || {
+                attempts.fetch_add(1, Ordering::Relaxed);
+                self.client.submit(signed_txn)
+            })
+            .await;
+
+        match res {
+            // an earlier attempt may have reached the node and only its answer was lost,
+            // the node then rejects the retry as a duplicate
+            Err(err) if attempts.load(Ordering::Relaxed) > 1 && is_client_error(&err) => {
+                match self.submitted_txn(signed_txn).await {
+                    Some(resp) => Ok(resp),
+                    None => Err(err),
+                }
+            }
+            res => res,
+        }
+    }
+
+    /// Pending transaction of `signed_txn` if the node knows it, pending or committed
+    async fn submitted_txn(
+        &self,
+        signed_txn: &SignedTransaction,
+    ) -> Option<Response<PendingTransaction>> {
+        let hash = signed_txn.clone().committed_hash();
+        let (txn, state) = self
+            .client
+            .get_transaction_by_hash(hash)
+            .await
+            .ok()?
+            .into_parts();
+        let pending = match txn {
+            Transaction::PendingTransaction(pending) => pending,
+            Transaction::UserTransaction(txn) => PendingTransaction {
+                hash: txn.info.hash,
+                request: txn.request,
+            },
+            _ => return None,
+        };
+        Some(Response::new(pending, state))
+    }
+
+    /// Submit a transaction signed offline from its bcs, returning its hash
+    pub async fn submit_signed_bcs(&self, signed_bcs: &[u8]) -> AppResult<HashValue> {
+        let signed_txn = SignedTxn::from_bcs(signed_bcs)?.into_inner();
+        let resp = self.submit_txn(&signed_txn).await.map_err(map_err!(
+            &EdsErr::SubmitTxnErr,
+            "offline signed transaction"
+        ))?;
+        Ok(resp.into_inner().hash)
+    }
+
+    async fn txn_builder(
+        &self,
+        payload: TransactionPayload,
+        overrides: Option<Overrides>,
+    ) -> AppResult<TransactionBuilder> {
         let chain_id = self.get_chain_id().await?;
-        let overrides = args.overrides.unwrap_or_default();
+        let overrides = overrides.unwrap_or_default();
 
         let expires_at = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -77,30 +220,176 @@ impl<'a> RestClient<'a> {
             .as_secs()
             + overrides.timeout_secs;
 
-        let payload = TransactionPayload::EntryFunction(args.entry_fn);
-        let txn_builder = TransactionBuilder::new(payload, expires_at, chain_id)
-            .sender(args.signer.address())
+        Ok(TransactionBuilder::new(payload, expires_at, chain_id)
             .max_gas_amount(overrides.max_gas_amount)
-            .gas_unit_price(overrides.gas_unit_price);
-
-        let signed_txn = args.signer.sign_with_transaction_builder(txn_builder);
-        self.client.submit(&signed_txn).await.map_err(map_err!(
-            &EdsErr::SubmitTxnErr,
-            format!("function[{fn_name}]")
-        ))
+            .gas_unit_price(overrides.gas_unit_price))
     }
 
     pub async fn view_fun<T: DeserializeOwned>(
         &self,
         args: ViewFnArgs,
     ) -> EndlessResult<Response<T>> {
-        self.client.view_bcs(&args.view_fn, None).await
+        self.retry
+            .run("view", || {
+                self.client.view_bcs(&args.view_fn, args.ledger_version)
+            })
+            .await
     }
 
     async fn get_index(&self) -> AppResult<Response<IndexResponse>> {
-        self.client
-            .get_index()
+        self.retry
+            .run("get_index", || self.client.get_index())
             .await
             .map_err(map_err!(&EdsErr::GetIndexErr))
     }
 }
+
+/// Accounts signing a transaction, taken from `EntryFnArgs`
+struct TxnSigners<'a> {
+    signer: &'a LocalAccount,
+    sequence_number: Option<u64>,
+    secondary_signers: Vec<&'a LocalAccount>,
+    fee_payer: Option<&'a LocalAccount>,
+}
+
+impl<'a> TxnSigners<'a> {
+    fn new(args: &EntryFnArgs<'a>) -> Self {
+        Self {
+            signer: args.signer,
+            sequence_number: args.sequence_number,
+            secondary_signers: args.secondary_signers.clone(),
+            fee_payer: args.fee_payer,
+        }
+    }
+
+    /// Sign with all signers, a sequence number taken from the signer is given back on failure
+    fn sign(&self, txn_builder: TransactionBuilder) -> AppResult<SignedTransaction> {
+        let seq_num = self
+            .sequence_number
+            .unwrap_or_else(|| self.signer.increment_sequence_number());
+        let raw_txn = txn_builder
+            .sender(self.signer.address())
+            .sequence_number(seq_num)
+            .build();
+
+        let (sender_key, secondary_signers) = (self.signer.private_key(), &self.secondary_signers);
+        let secondary_addrs = secondary_signers.iter().map(|s| s.address()).collect();
+        let secondary_keys = secondary_signers.iter().map(|s| s.private_key()).collect();
+
+        let signed_txn = match self.fee_payer {
+            Some(fee_payer) => raw_txn
+                .sign_fee_payer(
+                    sender_key,
+                    secondary_addrs,
+                    secondary_keys,
+                    fee_payer.address(),
+                    fee_payer.private_key(),
+                )
+                .map(|signed| signed.into_inner())
+                .map_err(map_err!(&EdsErr::SignFeePayerTxn)),
+            None if !secondary_signers.is_empty() => raw_txn
+                .sign_multi_agent(sender_key, secondary_addrs, secondary_keys)
+                .map(|signed| signed.into_inner())
+                .map_err(map_err!(&EdsErr::SignMultiAgentTxn)),
+            None => Ok(self.signer.sign_transaction(raw_txn)),
+        };
+
+        if signed_txn.is_err() && self.sequence_number.is_none() {
+            self.signer.decrement_sequence_number();
+        }
+        signed_txn
+    }
+}
+
+/// The node answered and refused the request, as opposed to failing to serve it
+fn is_client_error(err: &RestError) -> bool {
+    matches!(err, RestError::Api(resp) if resp.status_code.is_client_error())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::account_client::transfer_args;
+    use crate::error::is_err_code;
+    use crate::mock_node::{Endpoint, MockLedger, MockNode, test_account};
+    use endless_sdk::move_types::account_address::AccountAddress;
+    use endless_sdk::types::transaction::authenticator::TransactionAuthenticator;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn chain_id_cached_until_invalidated() {
+        let node = MockNode::start().await;
+        let client = node.client();
+        assert_eq!(client.rest_client().get_chain_id().await.unwrap().id(), 4);
+        assert_eq!(client.rest_client().get_chain_id().await.unwrap().id(), 4);
+        assert_eq!(node.requests_to(Endpoint::Index).len(), 1);
+
+        // the node comes back on another chain at the same url
+        node.set_ledger(MockLedger {
+            chain_id: 5,
+            ..node.ledger()
+        });
+        let err = client.get_index().await.unwrap_err();
+        assert!(is_err_code(&err, &EdsErr::ChainIdMismatch));
+        assert_eq!(client.rest_client().get_chain_id().await.unwrap().id(), 4);
+
+        client.invalidate_chain_id();
+        assert_eq!(client.get_index().await.unwrap().chain_id, 5);
+        assert_eq!(client.rest_client().get_chain_id().await.unwrap().id(), 5);
+    }
+
+    #[tokio::test]
+    async fn cached_chain_id_rechecked() {
+        let node = MockNode::start().await;
+        let client = node.client();
+        let cache = ChainIdCache::new(Duration::ZERO);
+        let rest_client = RestClient::new(client.get_client()).with_chain_id_cache(cache);
+        assert_eq!(rest_client.get_chain_id().await.unwrap().id(), 4);
+
+        node.set_ledger(MockLedger {
+            chain_id: 5,
+            ..node.ledger()
+        });
+        let err = rest_client.get_chain_id().await.unwrap_err();
+        assert!(is_err_code(&err, &EdsErr::ChainIdMismatch));
+        assert_eq!(node.requests_to(Endpoint::Index).len(), 2);
+    }
+
+    #[tokio::test]
+    async fn resubmit_after_lost_response_is_submitted() {
+        let node = MockNode::start().await;
+        node.set_pending_polls(usize::MAX);
+        node.drop_next(Endpoint::Submit);
+        let client = node.client();
+        let delay = Duration::from_millis(1);
+        let retry = RetryPolicy::new(3).with_backoff(delay, delay);
+        let rest_client = RestClient::new(client.get_client()).with_retry_policy(retry);
+
+        let from = test_account(0x11);
+        let args = transfer_args(&from, AccountAddress::ONE, 5, None).unwrap();
+        let signed_txn = rest_client.sign_entry_fun(args).await.unwrap();
+        let pending = rest_client.submit_txn(&signed_txn).await.unwrap();
+
+        let hash = signed_txn.clone().committed_hash();
+        assert_eq!(HashValue::from(hash), pending.into_inner().hash);
+        assert_eq!(node.submitted_txns(), vec![signed_txn.clone(), signed_txn]);
+        assert_eq!(node.requests_to(Endpoint::TxnByHash).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn multi_agent_txn_signed_by_secondary_signers() {
+        let node = MockNode::start().await;
+        let (from, second) = (test_account(0x11), test_account(0x22));
+        let args = transfer_args(&from, AccountAddress::ONE, 5, None).unwrap();
+        let args = args.with_secondary_signers(vec![&second]);
+
+        node.client().submit_entry_fn(args).await.unwrap();
+        match node.submitted_txns()[0].authenticator() {
+            TransactionAuthenticator::MultiAgent {
+                secondary_signer_addresses,
+                ..
+            } => assert_eq!(secondary_signer_addresses, vec![second.address()]),
+            other => panic!("expected a multi agent authenticator, got {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,305 @@
+use endless_sdk::rest_client::EndlessResult;
+use endless_sdk::rest_client::error::RestError;
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, SystemTime};
+use tracing::warn;
+
+/// Error classes a request can be retried on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RetryOn {
+    /// 429 Too Many Requests
+    RateLimited,
+    /// 5xx responses
+    ServerError,
+    /// Connection refused or reset before a response arrived
+    Connection,
+    /// Request timed out
+    Timeout,
+}
+
+impl RetryOn {
+    pub const ALL: [RetryOn; 4] = [
+        RetryOn::RateLimited,
+        RetryOn::ServerError,
+        RetryOn::Connection,
+        RetryOn::Timeout,
+    ];
+
+    /// Retry class of a rest error, None for errors retrying cannot fix
+    pub fn classify(err: &RestError) -> Option<Self> {
+        match err {
+            RestError::Api(resp) => Self::from_status(resp.status_code.as_u16()),
+            RestError::Http(_, err) if err.is_timeout() => Some(RetryOn::Timeout),
+            RestError::Http(_, err) if err.is_connect() || err.is_request() => {
+                Some(RetryOn::Connection)
+            }
+            RestError::Http(status, _) => Self::from_status(status.as_u16()),
+            RestError::Timeout(_) => Some(RetryOn::Timeout),
+            _ => None,
+        }
+    }
+
+    fn from_status(status: u16) -> Option<Self> {
+        match status {
+            429 => Some(RetryOn::RateLimited),
+            500..=599 => Some(RetryOn::ServerError),
+            _ => None,
+        }
+    }
+}
+
+/// Whether a rest error is the node's own failure, so another node may serve the call:
+/// the retryable classes of `RetryOn::classify`, or a ledger version the node does not have
+pub fn is_node_failure(err: &RestError) -> bool {
+    RetryOn::classify(err).is_some() || is_stale_ledger(err)
+}
+
+/// The node is behind the requested version, or has pruned it
+pub fn is_stale_ledger(err: &RestError) -> bool {
+    matches!(
+        api_error_code(err).as_deref(),
+        Some("version_not_found" | "version_pruned" | "block_not_found" | "block_pruned")
+    )
+}
+
+/// The requested version is older than the oldest one the node keeps
+pub fn is_pruned(err: &RestError) -> bool {
+    matches!(
+        api_error_code(err).as_deref(),
+        Some("version_pruned" | "block_pruned")
+    )
+}
+
+/// `error_code` of a node error response, as it is named in json
+pub(crate) fn api_error_code(err: &RestError) -> Option<String> {
+    let RestError::Api(resp) = err else {
+        return None;
+    };
+    let error_code = serde_json::to_value(&resp.error.error_code).ok()?;
+    error_code.as_str().map(str::to_string)
+}
+
+/// Count of rest calls that failed by `is_node_failure`, shared by the clones of a client,
+/// so that an error of a call can be told apart from errors every node would return
+#[derive(Debug, Clone, Default)]
+pub(crate) struct NodeFailures(Arc<AtomicUsize>);
+
+impl NodeFailures {
+    fn record(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn any(&self) -> bool {
+        self.0.load(Ordering::Relaxed) > 0
+    }
+}
+
+/// Retry policy of rest calls: exponential backoff with optional jitter.
+///
+/// Only idempotent calls go through it: views, simulations, reads,
+/// and submissions of an already signed transaction (resubmitting the same hash).
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total attempts including the first one, 1 disables retrying
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    pub jitter: bool,
+    pub retry_on: Vec<RetryOn>,
+    /// Where calls that fail by `is_node_failure` are recorded
+    failures: Option<NodeFailures>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+impl RetryPolicy {
+    /// Single attempt, no retry
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            base_delay: Duration::ZERO,
+            max_delay: Duration::ZERO,
+            jitter: false,
+            retry_on: vec![],
+            failures: None,
+        }
+    }
+
+    /// Retry every error class up to `max_attempts`, backing off from 200ms up to 5s
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(5),
+            jitter: true,
+            retry_on: RetryOn::ALL.to_vec(),
+            failures: None,
+        }
+    }
+
+    pub fn with_backoff(self, base_delay: Duration, max_delay: Duration) -> Self {
+        Self {
+            base_delay,
+            max_delay,
+            ..self
+        }
+    }
+
+    pub fn with_jitter(self, jitter: bool) -> Self {
+        Self { jitter, ..self }
+    }
+
+    pub fn with_retry_on(self, retry_on: Vec<RetryOn>) -> Self {
+        Self { retry_on, ..self }
+    }
+
+    /// Record every call that fails by `is_node_failure` in `failures`
+    pub(crate) fn with_node_failures(self, failures: NodeFailures) -> Self {
+        Self {
+            failures: Some(failures),
+            ..self
+        }
+    }
+
+    /// Whether `err` of the `attempt`-th attempt (1-based) is retried
+    pub fn should_retry(&self, err: &RestError, attempt: u32) -> bool {
+        attempt < self.max_attempts
+            && RetryOn::classify(err).is_some_and(|class| self.retry_on.contains(&class))
+    }
+
+    /// Delay after the `attempt`-th attempt (1-based)
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exp = attempt.saturating_sub(1).min(31);
+        let delay = self.base_delay.saturating_mul(1 << exp).min(self.max_delay);
+        if !self.jitter {
+            return delay;
+        }
+
+        // equal jitter: half fixed, half random
+        let half = delay.as_millis() as u64 / 2;
+        Duration::from_millis(half + random_u64() % (half + 1))
+    }
+
+    /// Run `op` until it succeeds, fails with a non-retryable error or runs out of attempts
+    pub async fn run<T, F, Fut>(&self, op_name: &str, mut op: F) -> EndlessResult<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = EndlessResult<T>>,
+    {
+        let mut attempt = 1;
+        loop {
+            match op().await {
+                Err(err) if self.should_retry(&err, attempt) => {
+                    let delay = self.backoff(attempt);
+                    warn!("{op_name} attempt {attempt} failed, retry in {delay:?}: {err}");
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                res => {
+                    match &self.failures {
+                        Some(failures) if res.as_ref().is_err_and(is_node_failure) => {
+                            failures.record()
+                        }
+                        _ => {}
+                    }
+                    return res;
+                }
+            }
+        }
+    }
+}
+
+fn random_u64() -> u64 {
+    RandomState::new().hash_one(SystemTime::now())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::account_client::AcctClientTrait;
+    use crate::client::types::Owner;
+    use crate::mock_node::{Endpoint, MockNode, MockResponse};
+    use endless_sdk::move_types::account_address::AccountAddress;
+
+    #[test]
+    fn backoff_doubles_up_to_max_delay() {
+        let policy = RetryPolicy::new(10)
+            .with_backoff(Duration::from_millis(100), Duration::from_secs(1))
+            .with_jitter(false);
+
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(4), Duration::from_millis(800));
+        assert_eq!(policy.backoff(5), Duration::from_secs(1));
+        assert_eq!(policy.backoff(64), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn jitter_stays_within_half_and_full_delay() {
+        let policy =
+            RetryPolicy::new(3).with_backoff(Duration::from_secs(1), Duration::from_secs(1));
+
+        for _ in 0..100 {
+            let delay = policy.backoff(1);
+            assert!(delay >= Duration::from_millis(500) && delay <= Duration::from_secs(1));
+        }
+    }
+
+    #[test]
+    fn status_classes() {
+        assert_eq!(RetryOn::from_status(429), Some(RetryOn::RateLimited));
+        assert_eq!(RetryOn::from_status(503), Some(RetryOn::ServerError));
+        assert_eq!(RetryOn::from_status(400), None);
+        assert_eq!(RetryOn::from_status(404), None);
+    }
+
+    #[tokio::test]
+    async fn run_retries_until_the_endpoint_recovers() {
+        let node = MockNode::start().await;
+        node.enqueue(Endpoint::Index, MockResponse::error(503, "overloaded"));
+        node.enqueue(Endpoint::Index, MockResponse::error(429, "slow down"));
+        let client = node.client();
+        let policy = RetryPolicy::new(3).with_backoff(Duration::ZERO, Duration::ZERO);
+
+        let res = policy.run("get_index", || client.get_client().get_index());
+        assert!(res.await.is_ok());
+        assert_eq!(node.requests_to(Endpoint::Index).len(), 3);
+
+        // out of attempts, and not retried at all on a client error
+        node.enqueue(Endpoint::Index, MockResponse::error(503, "overloaded"));
+        node.enqueue(Endpoint::Index, MockResponse::error(503, "overloaded"));
+        let policy = RetryPolicy::new(2).with_backoff(Duration::ZERO, Duration::ZERO);
+        let res = policy.run("get_index", || client.get_client().get_index());
+        assert!(res.await.is_err());
+        node.enqueue(Endpoint::Index, MockResponse::error(400, "bad request"));
+        let res = policy.run("get_index", || client.get_client().get_index());
+        assert!(res.await.is_err());
+        assert_eq!(node.requests_to(Endpoint::Index).len(), 6);
+    }
+
+    #[tokio::test]
+    async fn retries_server_errors_only() {
+        let node = MockNode::start().await;
+        node.set_view("endless_coin::balance", MockResponse::view(&7u128));
+        node.enqueue(Endpoint::View, MockResponse::error(503, "overloaded"));
+        node.enqueue(Endpoint::View, MockResponse::error(503, "overloaded"));
+
+        let owner = Owner::new(&AccountAddress::ONE);
+        let delay = Duration::from_millis(1);
+        let retry = RetryPolicy::new(3).with_backoff(delay, delay);
+        let client = node.client().with_retry_policy(retry);
+        assert_eq!(client.balance_of(owner).await.unwrap(), 7);
+        assert_eq!(node.requests_to(Endpoint::View).len(), 3);
+
+        node.enqueue(Endpoint::View, MockResponse::error(400, "bad argument"));
+        assert!(client.balance_of(owner).await.is_err());
+        assert_eq!(node.requests_to(Endpoint::View).len(), 4);
+    }
+}
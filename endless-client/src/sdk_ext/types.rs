@@ -1,30 +1,138 @@
 use crate::error::EdsErr;
-use base_infra::map_err;
 use base_infra::result::AppResult;
+use base_infra::{app_err, map_err};
 use endless_sdk::helper_client::Overrides;
 use endless_sdk::move_types::account_address::AccountAddress;
 use endless_sdk::move_types::identifier::Identifier;
 use endless_sdk::move_types::language_storage::{ModuleId, TypeTag};
-use endless_sdk::rest_client::endless_api_types::ViewFunction;
+use endless_sdk::rest_client::endless_api_types::{GasEstimation, ViewFunction};
 use endless_sdk::types::LocalAccount;
 use endless_sdk::types::chain_id::ChainId;
-use endless_sdk::types::transaction::EntryFunction;
+use endless_sdk::types::transaction::{
+    EntryFunction, Multisig, MultisigTransactionPayload, TransactionPayload,
+};
 use moka::future::Cache;
-use std::sync::OnceLock;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+static GAS_PRICE_CACHE: OnceLock<Cache<String, GasPrices>> = OnceLock::new();
+
+/// Gas price estimates move with mempool load, so they are only reused briefly
+const GAS_PRICE_TTL: Duration = Duration::from_secs(10);
+
+/// A cached chain id is checked against the node again after this long
+const CHAIN_ID_RECHECK: Duration = Duration::from_secs(60);
+
+/// Chain id of one node, shared by the clones of the client it belongs to
+#[derive(Debug, Clone)]
+pub(crate) struct ChainIdCache {
+    /// Chain id and when the node last reported it
+    cached: Arc<Mutex<Option<(ChainId, Instant)>>>,
+    recheck: Duration,
+}
+
+impl Default for ChainIdCache {
+    fn default() -> Self {
+        Self::new(CHAIN_ID_RECHECK)
+    }
+}
 
-static CHAIN_ID_CACHE: OnceLock<Cache<(), ChainId>> = OnceLock::new();
-pub(crate) struct ChainIdCache;
 impl ChainIdCache {
-    fn cache(&self) -> &'static Cache<(), ChainId> {
-        CHAIN_ID_CACHE.get_or_init(|| Cache::new(1))
+    pub fn new(recheck: Duration) -> Self {
+        Self {
+            cached: Arc::new(Mutex::new(None)),
+            recheck,
+        }
+    }
+
+    /// The cached chain id, None when there is none or it is due for a check
+    pub fn get(&self) -> Option<ChainId> {
+        let cached = *self.cached.lock().expect("chain id cache lock");
+        cached
+            .filter(|(_, checked_at)| checked_at.elapsed() < self.recheck)
+            .map(|(chain_id, _)| chain_id)
+    }
+
+    /// Cache the chain id reported by the node, or fail if it differs from the cached one
+    pub fn check(&self, node_url: &str, reported: ChainId) -> AppResult<ChainId> {
+        let mut cached = self.cached.lock().expect("chain id cache lock");
+        match *cached {
+            Some((chain_id, _)) if chain_id != reported => Err(app_err!(
+                &EdsErr::ChainIdMismatch,
+                format!(
+                    "node[{node_url}] cached chain_id[{chain_id}], reported chain_id[{reported}]"
+                )
+            )),
+            _ => {
+                *cached = Some((reported, Instant::now()));
+                Ok(reported)
+            }
+        }
     }
 
-    pub async fn get(&self) -> Option<ChainId> {
-        self.cache().get(&()).await
+    /// Forget the cached chain id, the next check takes whatever the node reports
+    pub fn invalidate(&self) {
+        *self.cached.lock().expect("chain id cache lock") = None;
+    }
+}
+
+/// Gas price cache keyed by node url
+pub(crate) struct GasPriceCache;
+impl GasPriceCache {
+    fn cache(&self) -> &'static Cache<String, GasPrices> {
+        GAS_PRICE_CACHE.get_or_init(|| {
+            Cache::builder()
+                .max_capacity(64)
+                .time_to_live(GAS_PRICE_TTL)
+                .build()
+        })
+    }
+
+    pub async fn get(&self, node_url: &str) -> Option<GasPrices> {
+        self.cache().get(node_url).await
+    }
+
+    pub async fn set(&self, node_url: &str, prices: GasPrices) {
+        self.cache().insert(node_url.to_string(), prices).await;
+    }
+}
+
+/// Priority of a transaction in the node's gas price estimate
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GasPriceLevel {
+    Deprioritized,
+    #[default]
+    Normal,
+    Prioritized,
+}
+
+/// Gas unit prices estimated by the node, one per `GasPriceLevel`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GasPrices {
+    pub deprioritized: u64,
+    pub normal: u64,
+    pub prioritized: u64,
+}
+
+impl GasPrices {
+    pub fn at(&self, level: GasPriceLevel) -> u64 {
+        match level {
+            GasPriceLevel::Deprioritized => self.deprioritized,
+            GasPriceLevel::Normal => self.normal,
+            GasPriceLevel::Prioritized => self.prioritized,
+        }
     }
+}
 
-    pub async fn set(&self, chain_id: ChainId) {
-        self.cache().insert((), chain_id).await;
+/// A node without separate estimates for a level prices it as normal
+impl From<GasEstimation> for GasPrices {
+    fn from(estimation: GasEstimation) -> Self {
+        let normal = estimation.gas_estimate;
+        Self {
+            deprioritized: estimation.deprioritized_gas_estimate.unwrap_or(normal),
+            normal,
+            prioritized: estimation.prioritized_gas_estimate.unwrap_or(normal),
+        }
     }
 }
 
@@ -34,6 +142,12 @@ pub struct EntryFnArgs<'a> {
     pub signer: &'a LocalAccount,
     pub entry_fn: EntryFunction,
     pub overrides: Option<Overrides>,
+    /// Gas unit price set by the caller, over the one of `overrides` and the fee policy's
+    pub gas_unit_price: Option<u64>,
+    pub sequence_number: Option<u64>,
+    pub secondary_signers: Vec<&'a LocalAccount>,
+    pub fee_payer: Option<&'a LocalAccount>,
+    pub multisig_address: Option<AccountAddress>,
     pub fn_name: String,
 }
 
@@ -66,6 +180,11 @@ impl<'a> EntryFnArgs<'a> {
             signer,
             entry_fn,
             overrides: None,
+            gas_unit_price: None,
+            sequence_number: None,
+            secondary_signers: vec![],
+            fee_payer: None,
+            multisig_address: None,
             fn_name,
         })
     }
@@ -73,12 +192,73 @@ impl<'a> EntryFnArgs<'a> {
     pub fn with_overrides(self, overrides: Option<Overrides>) -> Self {
         Self { overrides, ..self }
     }
+
+    /// Pay `gas_unit_price`, also under a fee policy, which only checks it against its limit
+    pub fn with_gas_unit_price(self, gas_unit_price: Option<u64>) -> Self {
+        Self {
+            gas_unit_price,
+            ..self
+        }
+    }
+
+    /// `overrides` with `gas_unit_price` if set, the gas the transaction is signed with
+    pub fn gas_overrides(&self) -> Option<Overrides> {
+        let Some(gas_unit_price) = self.gas_unit_price else {
+            return self.overrides.clone();
+        };
+        Some(Overrides {
+            gas_unit_price,
+            ..self.overrides.clone().unwrap_or_default()
+        })
+    }
+
+    /// Sign with an explicit sequence number instead of the signer's own
+    pub fn with_sequence_number(self, sequence_number: Option<u64>) -> Self {
+        Self {
+            sequence_number,
+            ..self
+        }
+    }
+
+    /// Multi-agent transaction, for entry functions taking a `&signer` of every party
+    pub fn with_secondary_signers(self, secondary_signers: Vec<&'a LocalAccount>) -> Self {
+        Self {
+            secondary_signers,
+            ..self
+        }
+    }
+
+    /// Sponsored transaction, gas is paid by `fee_payer` instead of the signer
+    pub fn with_fee_payer(self, fee_payer: Option<&'a LocalAccount>) -> Self {
+        Self { fee_payer, ..self }
+    }
+
+    /// Execute the entry function on behalf of a multisig account the signer owns
+    pub fn with_multisig_address(self, multisig_address: Option<AccountAddress>) -> Self {
+        Self {
+            multisig_address,
+            ..self
+        }
+    }
+
+    pub fn payload(&self) -> TransactionPayload {
+        let entry_fn = self.entry_fn.clone();
+        match self.multisig_address {
+            Some(multisig_address) => TransactionPayload::Multisig(Multisig {
+                multisig_address,
+                transaction_payload: Some(MultisigTransactionPayload::EntryFunction(entry_fn)),
+            }),
+            None => TransactionPayload::EntryFunction(entry_fn),
+        }
+    }
 }
 
 #[derive(Clone)]
 pub struct ViewFnArgs {
     pub module_address: AccountAddress,
     pub view_fn: ViewFunction,
+    /// Ledger version to view at, the latest if None
+    pub ledger_version: Option<u64>,
 }
 
 impl ViewFnArgs {
@@ -109,8 +289,16 @@ impl ViewFnArgs {
         Ok(Self {
             module_address,
             view_fn,
+            ledger_version: None,
         })
     }
+
+    pub fn with_ledger_version(self, ledger_version: Option<u64>) -> Self {
+        Self {
+            ledger_version,
+            ..self
+        }
+    }
 }
 
 fn parse_type_tag(s: &str) -> AppResult<TypeTag> {
@@ -118,6 +306,6 @@ fn parse_type_tag(s: &str) -> AppResult<TypeTag> {
         .map_err(map_err!(&EdsErr::ParseTypeArgs, s))
 }
 
-fn parse_type_tags(s: Vec<&str>) -> AppResult<Vec<TypeTag>> {
+pub(crate) fn parse_type_tags(s: Vec<&str>) -> AppResult<Vec<TypeTag>> {
     s.into_iter().map(|s| parse_type_tag(s)).collect()
 }
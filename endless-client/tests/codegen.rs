@@ -0,0 +1,35 @@
+//! Bindings generated from `fixtures/locking_coin_ex.json` compile against the crate's api
+
+include!("fixtures/locking_coin_ex.rs");
+
+use base_infra::result::AppResult;
+use endless_client::client::EnhancedClient;
+use endless_sdk::move_types::account_address::AccountAddress;
+use endless_sdk::types::LocalAccount;
+use locking_coin_ex::{LockingCoinExClient, UnlockAt, UnlockInfo};
+
+/// Type checks every generated method, never called
+#[allow(dead_code)]
+async fn call_bindings(client: &EnhancedClient, signer: &LocalAccount) -> AppResult<UnlockInfo> {
+    let locking = LockingCoinExClient::with_address(client, AccountAddress::ONE);
+    locking.claim(signer, AccountAddress::ONE, 5, None).await?;
+    locking
+        .get_unlock_info(AccountAddress::ONE, signer.address())
+        .await
+}
+
+#[test]
+fn generated_structs_round_trip_bcs() {
+    let info = UnlockInfo {
+        address: AccountAddress::ONE,
+        unlocked: 5,
+        unlock_list: vec![UnlockAt {
+            epoch: 2,
+            amount: 10,
+        }],
+    };
+    let decoded: UnlockInfo = bcs::from_bytes(&bcs::to_bytes(&info).unwrap()).unwrap();
+    assert_eq!(decoded.address, AccountAddress::ONE);
+    assert_eq!(decoded.unlock_list[0].amount, 10);
+    assert_eq!(locking_coin_ex::MODULE_NAME, "locking_coin_ex");
+}
@@ -0,0 +1,63 @@
+/// Generated from the abi of `0x1::locking_coin_ex`
+pub mod locking_coin_ex {
+    #![allow(dead_code, unused_imports, clippy::too_many_arguments)]
+
+    use ::base_infra::result::AppResult;
+    use ::endless_client::client::EnhancedClient;
+    use ::endless_client::error::EdsErr;
+    use ::endless_client::sdk_ext::types::{EntryFnArgs, ViewFnArgs};
+    use ::endless_client::utils::bcs_ext::BcsExt;
+    use ::endless_sdk::helper_client::Overrides;
+    use ::endless_sdk::move_types::account_address::AccountAddress;
+    use ::endless_sdk::move_types::u256::U256;
+    use ::endless_sdk::rest_client::{PendingTransaction, Response};
+    use ::endless_sdk::types::LocalAccount;
+    use ::std::str::FromStr;
+
+    pub const MODULE_ADDRESS: &str = "0x1";
+    pub const MODULE_NAME: &str = "locking_coin_ex";
+
+    #[derive(Debug, Clone, ::serde::Serialize, ::serde::Deserialize)]
+    pub struct UnlockAt {
+        pub epoch: u64,
+        pub amount: u128,
+    }
+
+    #[derive(Debug, Clone, ::serde::Serialize, ::serde::Deserialize)]
+    pub struct UnlockInfo {
+        pub address: AccountAddress,
+        pub unlocked: u128,
+        pub unlock_list: Vec<UnlockAt>,
+    }
+
+    pub struct LockingCoinExClient<'c> {
+        client: &'c EnhancedClient,
+        address: AccountAddress,
+    }
+
+    impl<'c> LockingCoinExClient<'c> {
+        /// Client of the module at the address of its abi
+        pub fn new(client: &'c EnhancedClient) -> Self {
+            let address = AccountAddress::from_str(MODULE_ADDRESS).expect("abi module address");
+            Self::with_address(client, address)
+        }
+
+        /// Client of the same module deployed at `address`
+        pub fn with_address(client: &'c EnhancedClient, address: AccountAddress) -> Self {
+            Self { client, address }
+        }
+
+        pub async fn claim(&self, signer: &LocalAccount, arg0: AccountAddress, arg1: u128, overrides: Option<Overrides>) -> AppResult<Response<PendingTransaction>> {
+            let args = vec![arg0.to_bytes()?, arg1.to_bytes()?];
+            let fn_args = EntryFnArgs::new(signer, self.address, MODULE_NAME, "claim", args, vec![])?;
+            self.client.submit_entry_fn(fn_args.with_overrides(overrides)).await
+        }
+
+        pub async fn get_unlock_info(&self, arg0: AccountAddress, arg1: AccountAddress) -> AppResult<UnlockInfo> {
+            let args = vec![arg0.to_bytes()?, arg1.to_bytes()?];
+            let fn_args = ViewFnArgs::new(self.address, MODULE_NAME, "get_unlock_info", args, vec![])?;
+            let ext_msg = Some(format!("{MODULE_NAME}::get_unlock_info"));
+            self.client.view_fn(fn_args, &EdsErr::ViewBcsErr, ext_msg).await
+        }
+    }
+}